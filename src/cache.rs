@@ -0,0 +1,343 @@
+use crate::error::Error;
+use crate::fetcher::{Feed, Fetcher};
+use crate::net::is_transient;
+use crate::parser::parse_document;
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How long a cached feed is served without fetching it again.
+pub const DEFAULT_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+const BODY_EXTENSION: &str = "xml";
+const META_EXTENSION: &str = "meta";
+
+/// A cached feed body and what is known about it.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    location: String,
+    body: String,
+    fetched: DateTime<Utc>,
+    pub_date: Option<DateTime<FixedOffset>>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Entry {
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+    pub fn fetched(&self) -> DateTime<Utc> {
+        self.fetched
+    }
+    /// The `pubDate` of the cached feed's channel, if it could be read.
+    pub fn pub_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.pub_date
+    }
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.fetched
+    }
+    /// Whether the entry is younger than `ttl`. One fetched in the future,
+    /// as a clock set wrongly at the time would leave it, never is.
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = self.age();
+        age >= Duration::zero() && age < ttl
+    }
+    fn to_feed(&self) -> Feed {
+        let mut feed = Feed::new(self.body.clone());
+        feed.set_fetched(self.fetched);
+        feed.set_etag(self.etag.clone());
+        feed.set_last_modified(self.last_modified.clone());
+        feed
+    }
+}
+
+/// Feed bodies stored on disk, one pair of files per location segment: the
+/// raw body and a small `key=value` metadata file beside it.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Cache {
+        Cache { dir: dir.into() }
+    }
+    /// `$XDG_CACHE_HOME/text_weather`, falling back to
+    /// `$HOME/.cache/text_weather`.
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+        };
+        Some(base.join("text_weather"))
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    pub fn load(&self, location: &str) -> Result<Option<Entry>, Error> {
+        let meta_path = self.path(location, META_EXTENSION);
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+        let meta = read(&meta_path)?;
+        let body = read(&self.path(location, BODY_EXTENSION))?;
+        let mut fetched = None;
+        let mut pub_date = None;
+        let mut etag = None;
+        let mut last_modified = None;
+        for line in meta.lines() {
+            match line.split_once('=') {
+                Some(("fetched", value)) => {
+                    fetched = DateTime::parse_from_rfc3339(value)
+                        .ok()
+                        .map(|date| date.with_timezone(&Utc))
+                }
+                Some(("pub_date", value)) => pub_date = DateTime::parse_from_rfc2822(value).ok(),
+                Some(("etag", value)) => etag = Some(String::from(value)),
+                Some(("last_modified", value)) => last_modified = Some(String::from(value)),
+                _ => (),
+            }
+        }
+        // An entry whose fetch time cannot be read is as good as missing.
+        Ok(fetched.map(|fetched| Entry {
+            location: String::from(location),
+            body,
+            fetched,
+            pub_date,
+            etag,
+            last_modified,
+        }))
+    }
+    pub fn store(&self, location: &str, feed: &Feed) -> Result<Entry, Error> {
+        let pub_date = match feed.channel() {
+            Some(channel) => Some(*channel.get_pub_date()),
+            None => parse_document(feed.body())
+                .ok()
+                .map(|channel| *channel.get_pub_date()),
+        };
+        let entry = Entry {
+            location: String::from(location),
+            body: String::from(feed.body()),
+            fetched: feed.fetched(),
+            pub_date,
+            etag: feed.etag().map(String::from),
+            last_modified: feed.last_modified().map(String::from),
+        };
+        let mut meta = format!("fetched={}\n", entry.fetched.to_rfc3339());
+        if let Some(pub_date) = entry.pub_date {
+            meta.push_str(&format!("pub_date={}\n", pub_date.to_rfc2822()));
+        }
+        // Header values cannot contain line breaks, so they need no escaping.
+        if let Some(etag) = &entry.etag {
+            meta.push_str(&format!("etag={}\n", etag));
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            meta.push_str(&format!("last_modified={}\n", last_modified));
+        }
+        fs::create_dir_all(&self.dir).map_err(|source| io_error(&self.dir, source))?;
+        write(&self.path(location, BODY_EXTENSION), &entry.body)?;
+        write(&self.path(location, META_EXTENSION), &meta)?;
+        Ok(entry)
+    }
+    /// Every readable entry, oldest fetch first.
+    pub fn list(&self) -> Result<Vec<Entry>, Error> {
+        let mut entries = Vec::new();
+        for location in self.locations()? {
+            if let Some(entry) = self.load(&location)? {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by_key(|entry| entry.fetched);
+        Ok(entries)
+    }
+    /// Removes the entry for `location`, returning whether there was one.
+    pub fn remove(&self, location: &str) -> Result<bool, Error> {
+        let mut removed = false;
+        for extension in [BODY_EXTENSION, META_EXTENSION].iter() {
+            let path = self.path(location, extension);
+            if path.exists() {
+                fs::remove_file(&path).map_err(|source| io_error(&path, source))?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+    /// Removes every entry, returning how many there were.
+    pub fn clear(&self) -> Result<usize, Error> {
+        let mut removed = 0;
+        for location in self.locations()? {
+            if self.remove(&location)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+    fn locations(&self) -> Result<Vec<String>, Error> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let entries = fs::read_dir(&self.dir).map_err(|source| io_error(&self.dir, source))?;
+        let mut locations = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|source| io_error(&self.dir, source))?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(META_EXTENSION) {
+                continue;
+            }
+            // Skip anything this cache could not have written.
+            if let Some(location) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(decode_key)
+            {
+                locations.push(location);
+            }
+        }
+        Ok(locations)
+    }
+    fn path(&self, location: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", encode_key(location), extension))
+    }
+}
+
+/// Serves feeds from a `Cache` while they are younger than the TTL, and
+/// otherwise fetches through the wrapped `Fetcher`. If that fails because
+/// the server cannot be reached or is overloaded, and an older copy is
+/// cached, the copy is returned marked as stale.
+pub struct CachingFetcher<F: Fetcher> {
+    inner: F,
+    cache: Cache,
+    ttl: Duration,
+}
+
+impl<F: Fetcher> CachingFetcher<F> {
+    pub fn new(inner: F, cache: Cache, ttl: std::time::Duration) -> CachingFetcher<F> {
+        CachingFetcher {
+            inner,
+            cache,
+            ttl: Duration::from_std(ttl).unwrap_or(Duration::MAX),
+        }
+    }
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
+
+impl<F: Fetcher> Fetcher for CachingFetcher<F> {
+    fn cache_key(&self, location: &str) -> String {
+        self.inner.cache_key(location)
+    }
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        let key = self.inner.cache_key(location);
+        // A damaged cache should never stop a fresh fetch.
+        let cached = self.cache.load(&key).unwrap_or(None);
+        if let Some(entry) = &cached {
+            if entry.is_fresh(self.ttl) {
+                return Ok(entry.to_feed());
+            }
+        }
+        let fetched = match &cached {
+            Some(entry) => self.inner.revalidate(location, &entry.to_feed()),
+            None => self.inner.fetch(location),
+        };
+        match fetched {
+            Ok(mut feed) => {
+                // Only cache bodies which parse, keeping the parse for whoever
+                // reads the feed, and treat a failed write like a cache miss
+                // next time.
+                if let Ok(channel) = parse_document(feed.body()) {
+                    feed.set_channel(channel);
+                    let _ = self.cache.store(&key, &feed);
+                }
+                Ok(feed)
+            }
+            // Only an unreachable or overloaded server is worth waiting out
+            // with an old copy. Anything else, such as an unknown location,
+            // would fail the same way again, and the copy would hide it.
+            Err(e) => match cached {
+                Some(entry) if is_transient(&e) => {
+                    let mut feed = entry.to_feed();
+                    feed.set_stale(true);
+                    Ok(feed)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| io_error(path, source))
+}
+
+/// Writes through a temporary file so readers never see half an entry. Each
+/// write gets its own temporary file, so that processes and threads storing
+/// the same location at once cannot write into each other's.
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temporary = path.with_file_name(name);
+    fs::write(&temporary, contents).map_err(|source| io_error(&temporary, source))?;
+    fs::rename(&temporary, path).map_err(|source| io_error(path, source))
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Location segments become file names with anything other than ASCII
+/// alphanumerics, '-' and '_' percent encoded.
+fn encode_key(location: &str) -> String {
+    let mut key = String::new();
+    for byte in location.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => key.push(byte as char),
+            _ => key.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    key
+}
+
+/// The location segment `key` was encoded from, or `None` if `encode_key`
+/// could not have produced it.
+fn decode_key(key: &str) -> Option<String> {
+    let bytes = key.as_bytes();
+    let mut decoded = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let hex = key
+                    .get(index + 1..index + 3)
+                    .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            }
+            byte @ (b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_') => {
+                decoded.push(byte);
+                index += 1;
+            }
+            _ => return None,
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
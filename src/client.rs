@@ -0,0 +1,101 @@
+use crate::error::Error;
+use crate::fetcher::{Feed, Fetcher, HttpFetcher};
+use crate::model::{Forecast, Observation};
+use crate::parse_feed;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// How many locations `Client::forecasts` fetches at once by default.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Fetches and parses forecasts through any `Fetcher`. With the default
+/// `HttpFetcher` every request shares one connection pool, so keep a single
+/// `Client` around rather than building one per location.
+pub struct Client<F: Fetcher = HttpFetcher> {
+    fetcher: F,
+    concurrency: usize,
+}
+
+impl<F: Fetcher> Client<F> {
+    pub fn new(fetcher: F) -> Client<F> {
+        Client {
+            fetcher,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+    /// Limits how many locations `feeds` and `forecasts` fetch at once.
+    /// Zero is treated as one.
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.concurrency = concurrency.max(1);
+    }
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+    pub fn fetcher(&self) -> &F {
+        &self.fetcher
+    }
+    pub fn feed(&self, location: &str) -> Result<Feed, Error> {
+        self.fetcher.fetch(location)
+    }
+    pub fn forecast(&self, location: &str) -> Result<Forecast, Error> {
+        parse_forecast(self.feed(location)?)
+    }
+    /// The latest observation for `location`, for a fetcher set up to
+    /// fetch observation feeds.
+    pub fn observation(&self, location: &str) -> Result<Observation, Error> {
+        parse_observation(self.feed(location)?)
+    }
+}
+
+impl<F: Fetcher + Sync> Client<F> {
+    /// Fetches every location, up to `concurrency` at a time. The results
+    /// are in the same order as `locations`, and one location failing does
+    /// not stop the others.
+    pub fn feeds(&self, locations: &[&str]) -> Vec<Result<Feed, Error>> {
+        self.map_parallel(locations, |location| self.feed(location))
+    }
+    /// As `feeds`, but parsed into forecasts.
+    pub fn forecasts(&self, locations: &[&str]) -> Vec<Result<Forecast, Error>> {
+        self.map_parallel(locations, |location| self.forecast(location))
+    }
+    fn map_parallel<T, M>(&self, locations: &[&str], map: M) -> Vec<Result<T, Error>>
+    where
+        T: Send,
+        M: Fn(&str) -> Result<T, Error> + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<T, Error>>>> =
+            Mutex::new(locations.iter().map(|_| None).collect());
+        let workers = self.concurrency.min(locations.len());
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= locations.len() {
+                        break;
+                    }
+                    let result = map(locations[index]);
+                    results.lock().unwrap()[index] = Some(result);
+                });
+            }
+        });
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.expect("every location is fetched exactly once"))
+            .collect()
+    }
+}
+
+pub(crate) fn parse_forecast(mut feed: Feed) -> Result<Forecast, Error> {
+    let parsed = parse_feed(&mut feed)?;
+    Forecast::parse_from_items(parsed.get_items())
+}
+
+pub(crate) fn parse_observation(mut feed: Feed) -> Result<Observation, Error> {
+    let parsed = parse_feed(&mut feed)?;
+    Observation::parse_from_items(parsed.get_items())
+}
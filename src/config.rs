@@ -0,0 +1,286 @@
+//! Settings read from `config.toml`, for example:
+//!
+//! ```toml
+//! default_location = "home"
+//! units = "metric"
+//! output = "text"
+//! color = "auto"
+//! icons = "emoji"
+//! cache_ttl = 900
+//!
+//! [locations]
+//! home = "2643743"
+//! office = "Bristol"
+//!
+//! [http]
+//! language = "cy"
+//! timeout = 20
+//! ```
+//!
+//! Every setting is optional. Location aliases may name a location id, a
+//! postcode district or a place in the gazetteer. `format` takes a template
+//! as `--format` does, and is used instead of `output` when both are set.
+//! A `[prose]` table translates the sentences of `--output prose` and
+//! `ssml`; its keys are the fields of `prose::Phrases`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::Error;
+use crate::fetcher::HttpOptions;
+use crate::icon::Icons;
+use crate::language::Language;
+use crate::output::OutputFormat;
+use crate::prose::Phrases;
+use crate::style::ColorChoice;
+use crate::template::Template;
+use crate::units::Units;
+
+const FILE_NAME: &str = "config.toml";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    default_location: Option<String>,
+    units: Option<Units>,
+    output: Option<OutputFormat>,
+    /// A template for each day, used instead of `output`.
+    format: Option<String>,
+    color: Option<ColorChoice>,
+    /// Pictures for each day: ascii, emoji or none.
+    icons: Option<Icons>,
+    /// Seconds to serve a cached feed for.
+    cache_ttl: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    locations: BTreeMap<String, String>,
+    http: HttpConfig,
+    /// Phrases for `--output prose` and `ssml`, to translate them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prose: Option<Phrases>,
+}
+
+/// The `[http]` table, matching `HttpOptions`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    base_url: Option<String>,
+    language: Option<Language>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ca_certificates: Vec<PathBuf>,
+    /// Seconds.
+    connect_timeout: Option<u64>,
+    /// Seconds.
+    timeout: Option<u64>,
+    retries: Option<u32>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/text_weather/config.toml`, or
+    /// `$HOME/.config/text_weather/config.toml` if XDG_CONFIG_HOME is unset.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("text_weather").join(FILE_NAME))
+    }
+    /// Reads and validates the config file at `path`, or returns `None` if
+    /// there is no file there.
+    pub fn load(path: &Path) -> Result<Option<Config>, Error> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(Error::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        };
+        let config: Config = toml::from_str(&contents).map_err(|source| Error::ConfigSyntax {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let problems = config.problems();
+        if problems.is_empty() {
+            Ok(Some(config))
+        } else {
+            Err(Error::InvalidConfig {
+                path: path.display().to_string(),
+                problems,
+            })
+        }
+    }
+    /// Every setting that cannot be used, such as aliases that do not
+    /// resolve, malformed urls and missing certificates, with its name.
+    pub fn problems(&self) -> Vec<(String, Error)> {
+        let mut problems = Vec::new();
+        for (alias, location) in &self.locations {
+            if let Err(e) = crate::resolve_location(location) {
+                problems.push((format!("locations.{}", alias), e));
+            }
+        }
+        if let Some(location) = &self.default_location {
+            if let Err(e) = self.location(location) {
+                problems.push((String::from("default_location"), e));
+            }
+        }
+        if let Some(format) = &self.format {
+            if let Err(e) = Template::parse(format) {
+                problems.push((String::from("format"), e));
+            }
+        }
+        for (key, e) in self.http_settings().1 {
+            problems.push((format!("http.{}", key), e));
+        }
+        problems
+    }
+    pub fn default_location(&self) -> Option<&str> {
+        self.default_location.as_deref()
+    }
+    pub fn units(&self) -> Option<Units> {
+        self.units
+    }
+    pub fn output(&self) -> Option<OutputFormat> {
+        self.output
+    }
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+    pub fn color(&self) -> Option<ColorChoice> {
+        self.color
+    }
+    pub fn icons(&self) -> Option<Icons> {
+        self.icons
+    }
+    /// The `[prose]` phrases, or English if there are none.
+    pub fn phrases(&self) -> Phrases {
+        self.prose.clone().unwrap_or_default()
+    }
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache_ttl.map(Duration::from_secs)
+    }
+    /// The named locations, by alias.
+    pub fn locations(&self) -> &BTreeMap<String, String> {
+        &self.locations
+    }
+    /// What the alias `name` stands for, if it is one.
+    pub fn alias(&self, name: &str) -> Option<&str> {
+        self.locations.get(name.trim()).map(String::as_str)
+    }
+    /// The location id for `name`, which may be an alias, a location id, a
+    /// postcode district or a place name.
+    pub fn location(&self, name: &str) -> Result<String, Error> {
+        crate::resolve_location(self.alias(name).unwrap_or(name))
+    }
+    /// `HttpOptions::default()` with the `[http]` settings applied, or why
+    /// the first unusable setting cannot be used.
+    pub fn http_options(&self) -> Result<HttpOptions, Error> {
+        let (options, mut problems) = self.http_settings();
+        if problems.is_empty() {
+            Ok(options)
+        } else {
+            Err(problems.remove(0).1)
+        }
+    }
+    /// `HttpOptions::default()` with every usable `[http]` setting applied,
+    /// and the key of each setting that cannot be used.
+    fn http_settings(&self) -> (HttpOptions, Vec<(&'static str, Error)>) {
+        let http = &self.http;
+        let mut options = HttpOptions::default();
+        let mut problems = Vec::new();
+        if let Some(base_url) = &http.base_url {
+            match Url::parse(base_url) {
+                Ok(url) if url.cannot_be_a_base() => {
+                    problems.push(("base_url", Error::InvalidBaseUrl(url)))
+                }
+                Ok(url) => options.set_base_url(url),
+                Err(source) => problems.push((
+                    "base_url",
+                    Error::Url {
+                        field: "base_url",
+                        value: base_url.clone(),
+                        source,
+                    },
+                )),
+            }
+        }
+        if let Some(language) = http.language {
+            options.set_language(language);
+        }
+        if let Some(user_agent) = &http.user_agent {
+            match HeaderValue::from_str(user_agent) {
+                Ok(_) => options.set_user_agent(user_agent),
+                Err(_) => problems.push((
+                    "user_agent",
+                    Error::Conversion {
+                        field: String::from("user_agent"),
+                        value: user_agent.clone(),
+                        reason: "expected printable ASCII characters only",
+                    },
+                )),
+            }
+        }
+        if let Some(proxy) = &http.proxy {
+            match Url::parse(proxy) {
+                Ok(_) => options.set_proxy(Some(proxy)),
+                Err(source) => problems.push((
+                    "proxy",
+                    Error::Url {
+                        field: "proxy",
+                        value: proxy.clone(),
+                        source,
+                    },
+                )),
+            }
+        }
+        for path in &http.ca_certificates {
+            match std::fs::metadata(path) {
+                Ok(_) => options.add_ca_certificate(path),
+                Err(source) => problems.push((
+                    "ca_certificates",
+                    Error::Io {
+                        path: path.display().to_string(),
+                        source,
+                    },
+                )),
+            }
+        }
+        if let Some(seconds) = http.connect_timeout {
+            match timeout("connect_timeout", seconds) {
+                Ok(timeout) => options.set_connect_timeout(timeout),
+                Err(e) => problems.push(("connect_timeout", e)),
+            }
+        }
+        if let Some(seconds) = http.timeout {
+            match timeout("timeout", seconds) {
+                Ok(timeout) => options.set_timeout(timeout),
+                Err(e) => problems.push(("timeout", e)),
+            }
+        }
+        if let Some(retries) = http.retries {
+            options.set_retries(retries);
+        }
+        (options, problems)
+    }
+}
+
+/// The timeout setting `key` of `seconds`, which would fail every request if
+/// it were zero.
+fn timeout(key: &str, seconds: u64) -> Result<Duration, Error> {
+    match seconds {
+        0 => Err(Error::Conversion {
+            field: String::from(key),
+            value: String::from("0"),
+            reason: "expected at least one second",
+        }),
+        seconds => Ok(Duration::from_secs(seconds)),
+    }
+}
@@ -1,7 +1,61 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::convert::TryFrom;
+
+/// A day of the forecast, chosen relative to when it was issued,
+/// by weekday, or by date.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Day {
     Today,
     Tomorrow,
     Overmorrow,
+    Weekday(Weekday),
+    Date(NaiveDate),
+}
+
+impl Day {
+    /// Which day of a forecast of `days` days issued on `issued` this is,
+    /// counting from zero, or `None` if the forecast does not reach it. A
+    /// weekday means its next occurrence, which may be `issued` itself.
+    pub fn index(&self, issued: NaiveDate, days: usize) -> Option<usize> {
+        let index = match self {
+            Day::Today => 0,
+            Day::Tomorrow => 1,
+            Day::Overmorrow => 2,
+            Day::Weekday(weekday) => {
+                let from = issued.weekday().num_days_from_monday();
+                (weekday.num_days_from_monday() + 7 - from) as i64 % 7
+            }
+            Day::Date(date) => (*date - issued).num_days(),
+        };
+        usize::try_from(index).ok().filter(|index| *index < days)
+    }
+}
+
+impl std::str::FromStr for Day {
+    type Err = String;
+
+    /// Accepts today, tomorrow, overmorrow, weekday names such as "tuesday"
+    /// or "tue", and ISO 8601 dates such as "2026-10-20".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        match lower.as_str() {
+            "today" => return Ok(Day::Today),
+            "tomorrow" => return Ok(Day::Tomorrow),
+            "overmorrow" => return Ok(Day::Overmorrow),
+            _ => (),
+        }
+        if let Ok(weekday) = lower.parse::<Weekday>() {
+            return Ok(Day::Weekday(weekday));
+        }
+        match NaiveDate::parse_from_str(&lower, "%Y-%m-%d") {
+            Ok(date) => Ok(Day::Date(date)),
+            Err(_) => Err(format!(
+                "\"{}\" is not a day, expected today, tomorrow, overmorrow, a weekday \
+                 or a date such as 2026-10-20",
+                s
+            )),
+        }
+    }
 }
 
 impl std::fmt::Display for Day {
@@ -10,6 +64,14 @@ impl std::fmt::Display for Day {
             Day::Today => "Today",
             Day::Tomorrow => "Tomorrow",
             Day::Overmorrow => "Overmorrow",
+            Day::Weekday(Weekday::Mon) => "Monday",
+            Day::Weekday(Weekday::Tue) => "Tuesday",
+            Day::Weekday(Weekday::Wed) => "Wednesday",
+            Day::Weekday(Weekday::Thu) => "Thursday",
+            Day::Weekday(Weekday::Fri) => "Friday",
+            Day::Weekday(Weekday::Sat) => "Saturday",
+            Day::Weekday(Weekday::Sun) => "Sunday",
+            Day::Date(date) => return write!(f, "{}", date.format("%A %Y-%m-%d")),
         };
         write!(f, "{}", day_rep)
     }
@@ -0,0 +1,259 @@
+//! Forecasts as a document to post or send: GitHub-flavoured Markdown for
+//! wikis, and self-contained HTML, styled inline so email clients keep it.
+//!
+//! Every location is a section of the same document, with the feed's
+//! metadata, a table of every detail with a column a day, and a link to BBC
+//! Weather. The BBC's copyright notice ends the document.
+
+use std::io::{self, Write};
+
+use crate::model::Details;
+use crate::render::{self, RenderOptions, Renderer};
+use crate::{output, LocationForecast};
+
+const TITLE: &str = "Weather forecast";
+
+const BODY_STYLE: &str = "font-family: sans-serif; color: #222; max-width: 60em; margin: 1em auto;";
+const CAPTION_STYLE: &str = "color: #555;";
+const STALE_STYLE: &str = "color: #b00; font-weight: bold;";
+const TABLE_STYLE: &str = "border-collapse: collapse; margin: 1em 0;";
+const HEADING_STYLE: &str =
+    "border: 1px solid #ccc; padding: 0.3em 0.6em; background: #eef; text-align: left;";
+const CELL_STYLE: &str = "border: 1px solid #ccc; padding: 0.3em 0.6em;";
+const FOOTER_STYLE: &str = "color: #555; font-size: smaller; margin-top: 2em;";
+
+/// GitHub-flavoured Markdown.
+pub struct Markdown;
+
+impl Renderer for Markdown {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        writeln!(out, "# {}", TITLE)?;
+        for forecast in forecasts {
+            let section = Section::new(forecast, options);
+            writeln!(out)?;
+            writeln!(out, "## {}", markdown(&section.name))?;
+            writeln!(out)?;
+            writeln!(out, "{}", markdown(&section.description))?;
+            writeln!(out)?;
+            for (label, value) in &section.metadata {
+                writeln!(out, "- **{}:** {}", label, markdown(value))?;
+            }
+            writeln!(out, "- **Source:** [BBC Weather](<{}>)", section.link)?;
+            if let Some(stale) = &section.stale {
+                writeln!(out)?;
+                writeln!(out, "> **{}**", markdown(stale))?;
+            }
+            writeln!(out)?;
+            let headings: Vec<String> = section.headings.iter().map(|h| markdown(h)).collect();
+            writeln!(out, "| | {} |", headings.join(" | "))?;
+            writeln!(out, "|---|{}", "---|".repeat(headings.len()))?;
+            for (label, values) in &section.rows {
+                let values: Vec<String> = values.iter().map(|v| markdown(v)).collect();
+                writeln!(out, "| **{}** | {} |", label, values.join(" | "))?;
+            }
+        }
+        let copyrights = copyrights(forecasts);
+        if !copyrights.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "---")?;
+            writeln!(out)?;
+            for copyright in copyrights {
+                writeln!(out, "{}", markdown(copyright))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One HTML page with its styles inline.
+pub struct Html;
+
+impl Renderer for Html {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let language = forecasts
+            .first()
+            .map(|forecast| forecast.channel().get_language())
+            .unwrap_or("en");
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, r#"<html lang="{}">"#, html(language))?;
+        writeln!(out, "<head>")?;
+        writeln!(out, r#"<meta charset="utf-8">"#)?;
+        writeln!(
+            out,
+            r#"<meta name="viewport" content="width=device-width, initial-scale=1">"#
+        )?;
+        writeln!(out, "<title>{}</title>", TITLE)?;
+        writeln!(out, "</head>")?;
+        writeln!(out, r#"<body style="{}">"#, BODY_STYLE)?;
+        writeln!(out, "<h1>{}</h1>", TITLE)?;
+        for forecast in forecasts {
+            let section = Section::new(forecast, options);
+            writeln!(out, "<section>")?;
+            writeln!(out, "<h2>{}</h2>", html(&section.name))?;
+            writeln!(
+                out,
+                r#"<p style="{}">{}</p>"#,
+                CAPTION_STYLE,
+                html(&section.description)
+            )?;
+            writeln!(out, "<ul>")?;
+            for (label, value) in &section.metadata {
+                writeln!(out, "<li><strong>{}:</strong> {}</li>", label, html(value))?;
+            }
+            writeln!(
+                out,
+                r#"<li><strong>Source:</strong> <a href="{}">BBC Weather</a></li>"#,
+                html(&section.link)
+            )?;
+            writeln!(out, "</ul>")?;
+            if let Some(stale) = &section.stale {
+                writeln!(out, r#"<p style="{}">{}</p>"#, STALE_STYLE, html(stale))?;
+            }
+            writeln!(out, r#"<table style="{}">"#, TABLE_STYLE)?;
+            write!(out, r#"<thead><tr><th style="{}"></th>"#, HEADING_STYLE)?;
+            for heading in &section.headings {
+                write!(
+                    out,
+                    r#"<th scope="col" style="{}">{}</th>"#,
+                    HEADING_STYLE,
+                    html(heading)
+                )?;
+            }
+            writeln!(out, "</tr></thead>")?;
+            writeln!(out, "<tbody>")?;
+            for (label, values) in &section.rows {
+                write!(
+                    out,
+                    r#"<tr><th scope="row" style="{}">{}</th>"#,
+                    HEADING_STYLE, label
+                )?;
+                for value in values {
+                    write!(out, r#"<td style="{}">{}</td>"#, CELL_STYLE, html(value))?;
+                }
+                writeln!(out, "</tr>")?;
+            }
+            writeln!(out, "</tbody>")?;
+            writeln!(out, "</table>")?;
+            writeln!(out, "</section>")?;
+        }
+        writeln!(out, r#"<footer style="{}">"#, FOOTER_STYLE)?;
+        for copyright in copyrights(forecasts) {
+            writeln!(out, "<p>{}</p>", html(copyright))?;
+        }
+        writeln!(out, "</footer>")?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")
+    }
+}
+
+/// What a document shows for one location.
+struct Section {
+    name: String,
+    description: String,
+    metadata: Vec<(&'static str, String)>,
+    link: String,
+    stale: Option<String>,
+    headings: Vec<String>,
+    /// Each detail, labelled, with its value for every day.
+    rows: Vec<(&'static str, Vec<String>)>,
+}
+
+impl Section {
+    fn new(forecast: &LocationForecast, options: &RenderOptions) -> Section {
+        let channel = forecast.channel();
+        let metadata = vec![
+            ("Location", String::from(forecast.location())),
+            (
+                "Issued",
+                channel
+                    .get_pub_date()
+                    .format("%Y-%m-%d %H:%M %:z")
+                    .to_string(),
+            ),
+            (
+                "Fetched",
+                forecast
+                    .feed()
+                    .fetched()
+                    .format("%Y-%m-%d %H:%M UTC")
+                    .to_string(),
+            ),
+            ("Language", String::from(channel.get_language())),
+        ];
+        let mut headings = Vec::new();
+        let mut summaries = Vec::new();
+        let mut details = Vec::new();
+        for (index, daily) in forecast.days() {
+            headings.push(render::dated_day_name(forecast, index));
+            summaries.push(String::from(daily.summary().summary()));
+            details.push(daily.details().in_units(options.units()));
+        }
+        let row =
+            |value: fn(&Details) -> &str| details.iter().map(|d| String::from(value(d))).collect();
+        let rows = vec![
+            ("Summary", summaries),
+            ("Maximum Temperature", row(|d| d.temperature_max())),
+            ("Minimum Temperature", row(|d| d.temperature_min())),
+            ("Wind Direction", row(|d| d.wind_direction())),
+            ("Wind Speed", row(|d| d.wind_speed())),
+            ("Visibility", row(|d| d.visibility())),
+            ("Pressure", row(|d| d.pressure())),
+            ("Humidity", row(|d| d.humidity())),
+            ("UV Risk", row(|d| d.uv_risk())),
+            ("Pollution", row(|d| d.pollution_level())),
+            ("Sunrise", row(|d| d.sunrise_time())),
+            ("Sunset", row(|d| d.sunset_time())),
+        ];
+        Section {
+            name: output::location_name(channel.get_title()),
+            description: String::from(channel.get_description()),
+            metadata,
+            link: channel.get_link().to_string(),
+            stale: render::stale_message(forecast.feed()),
+            headings,
+            rows,
+        }
+    }
+}
+
+/// Each different copyright notice, in the order they first appear.
+fn copyrights(forecasts: &[LocationForecast]) -> Vec<&str> {
+    let mut copyrights: Vec<&str> = Vec::new();
+    for forecast in forecasts {
+        let copyright = forecast.channel().get_copyright();
+        if !copyright.is_empty() && !copyrights.contains(&copyright) {
+            copyrights.push(copyright);
+        }
+    }
+    copyrights
+}
+
+/// `text` with Markdown's special characters escaped, safe in a table cell.
+/// A leading `-` or `+` would start a list item, so it is escaped too.
+fn markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (index, c) in text.chars().enumerate() {
+        if "\\`*_[]<>|#!".contains(c) || (index == 0 && "-+".contains(c)) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,42 +1,433 @@
 use std::fmt;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::gazetteer::Place;
 
 #[derive(Debug)]
 pub enum Error {
-    Reqwest,
-    Conversion,
-    InvalidDestination,
-    InvalidSegment,
-    ParseLibrary,
+    Reqwest {
+        url: Url,
+        source: reqwest::Error,
+    },
+    Client {
+        source: reqwest::Error,
+    },
+    Url {
+        field: &'static str,
+        value: String,
+        source: url::ParseError,
+    },
+    Date {
+        field: &'static str,
+        value: String,
+        source: chrono::format::ParseError,
+    },
+    Conversion {
+        field: String,
+        value: String,
+        reason: &'static str,
+    },
+    Day {
+        day: usize,
+        source: Box<Error>,
+    },
+    MissingDay {
+        day: usize,
+        available: usize,
+    },
+    DayNotForecast {
+        day: String,
+        issued: chrono::NaiveDate,
+        /// How many days the forecast covers.
+        days: usize,
+    },
+    NoObservation,
+    InvalidDestination {
+        element: &'static str,
+        destination: &'static str,
+    },
+    InvalidLocation(String),
+    InvalidBaseUrl(Url),
+    UnknownPlace(String),
+    InvalidCoordinates {
+        latitude: f64,
+        longitude: f64,
+    },
+    AmbiguousPlace {
+        query: String,
+        candidates: Vec<Place>,
+    },
+    Certificate {
+        path: String,
+        source: reqwest::Error,
+    },
+    Proxy {
+        proxy: String,
+        source: reqwest::Error,
+    },
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    ConfigSyntax {
+        path: String,
+        source: toml::de::Error,
+    },
+    InvalidConfig {
+        path: String,
+        problems: Vec<(String, Error)>,
+    },
+    NotFound {
+        location: String,
+    },
+    RateLimited {
+        url: Url,
+        retry_after: Option<Duration>,
+    },
+    ServerError {
+        url: Url,
+        status: u16,
+    },
+    UnexpectedStatus {
+        url: Url,
+        status: u16,
+    },
+    UnexpectedContentType {
+        url: Url,
+        content_type: String,
+    },
+    Template {
+        template: String,
+        column: usize,
+        reason: String,
+    },
+    UnknownRenderer {
+        name: String,
+        available: Vec<String>,
+    },
+    Output {
+        format: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    Locations {
+        attempted: usize,
+        failures: Vec<(String, Error)>,
+    },
+    Parse(ParseError),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::Reqwest => write!(f, "An error occured in reqwest"),
-            Error::Conversion => write!(f, "A type conversion error occurred"),
-            Error::InvalidDestination => {
-                write!(f, "An element was placed in an invalid destination")
+            Error::Reqwest { url, .. } => write!(f, "Failed to fetch {}", url),
+            Error::Client { .. } => write!(f, "Could not create the HTTP client"),
+            Error::Url { field, value, .. } => {
+                write!(f, "The {} \"{}\" is not a valid url", field, value)
+            }
+            Error::Date { field, value, .. } => {
+                write!(
+                    f,
+                    "The {} \"{}\" is not a valid RFC 2822 date",
+                    field, value
+                )
+            }
+            Error::Conversion {
+                field,
+                value,
+                reason,
+            } => write!(f, "Could not read {} from \"{}\": {}", field, value, reason),
+            Error::Day { day, .. } => write!(f, "Could not read day {} of the forecast", day + 1),
+            Error::MissingDay { day, available } => write!(
+                f,
+                "The forecast has no day {}, only {} day(s) are available",
+                day + 1,
+                available
+            ),
+            Error::DayNotForecast { day, issued, days } => {
+                write!(f, "There is no forecast for {}, ", day)?;
+                match days.checked_sub(1) {
+                    Some(last) => write!(
+                        f,
+                        "the forecast issued on {} covers {} to {}",
+                        issued,
+                        issued,
+                        *issued + chrono::Duration::days(last as i64)
+                    ),
+                    None => write!(f, "the forecast issued on {} has no days", issued),
+                }
+            }
+            Error::NoObservation => write!(f, "The feed has no observation in it"),
+            Error::InvalidDestination {
+                element,
+                destination,
+            } => write!(
+                f,
+                "A <{}> element was placed in an invalid destination: {}",
+                element, destination
+            ),
+            Error::InvalidLocation(location) => write!(
+                f,
+                "\"{}\" is not a valid location, expected a numeric GeoNames id such as \
+                 2643743 or a UK postcode district such as SW1A",
+                location
+            ),
+            Error::InvalidBaseUrl(url) => {
+                write!(
+                    f,
+                    "The base url {} cannot have a feed path added to it",
+                    url
+                )
+            }
+            Error::UnknownPlace(query) => write!(
+                f,
+                "No known place is called \"{}\", search for it or use its location id",
+                query
+            ),
+            Error::InvalidCoordinates {
+                latitude,
+                longitude,
+            } => write!(
+                f,
+                "{}, {} are not valid coordinates, expected a latitude from -90 to 90 \
+                 and a longitude from -180 to 180",
+                latitude, longitude
+            ),
+            Error::AmbiguousPlace { query, candidates } => {
+                write!(f, "\"{}\" matches {} places", query, candidates.len())?;
+                if let Some(first) = candidates.first() {
+                    let others = candidates.get(1..).unwrap_or_default();
+                    let qualifier = if others
+                        .iter()
+                        .any(|place| place.country() == first.country())
+                    {
+                        first.admin()
+                    } else {
+                        first.country()
+                    };
+                    write!(
+                        f,
+                        ", add a country or region such as \"{}, {}\" to choose one:",
+                        first.name(),
+                        qualifier
+                    )?;
+                }
+                for place in candidates {
+                    write!(f, "\n  {}", place)?;
+                }
+                Ok(())
+            }
+            Error::Certificate { path, .. } => {
+                write!(f, "Could not load the CA certificate {}", path)
+            }
+            Error::Proxy { proxy, .. } => write!(f, "Could not use the proxy {}", proxy),
+            Error::Io { path, .. } => write!(f, "Could not read {}", path),
+            Error::ConfigSyntax { path, .. } => {
+                write!(f, "Could not parse the config file {}", path)
+            }
+            Error::InvalidConfig { path, problems } => {
+                write!(
+                    f,
+                    "The config file {} has {} problem(s)",
+                    path,
+                    problems.len()
+                )?;
+                for (setting, e) in problems {
+                    write!(f, "\n  {}: {}", setting, e)?;
+                    let mut source = std::error::Error::source(e);
+                    while let Some(cause) = source {
+                        write!(f, ": {}", cause)?;
+                        source = cause.source();
+                    }
+                }
+                Ok(())
+            }
+            Error::NotFound { location } => {
+                write!(f, "location id {} not recognised by BBC Weather", location)
+            }
+            Error::RateLimited { url, retry_after } => {
+                write!(f, "BBC Weather is rate limiting requests to {}", url)?;
+                match retry_after {
+                    Some(delay) => write!(f, ", retry after {} seconds", delay.as_secs()),
+                    None => Ok(()),
+                }
             }
-            Error::InvalidSegment => write!(f, "An invalid url segment was provided"),
-            Error::ParseLibrary => write!(f, "An error occurred in quick_xml"),
+            Error::ServerError { url, status } => write!(
+                f,
+                "BBC Weather returned a server error ({}) for {}, try again later",
+                status, url
+            ),
+            Error::UnexpectedStatus { url, status } => {
+                write!(f, "BBC Weather returned status {} for {}", status, url)
+            }
+            Error::UnexpectedContentType { url, content_type } => write!(
+                f,
+                "Expected an RSS feed from {} but received \"{}\"",
+                url, content_type
+            ),
+            Error::Template {
+                template,
+                column,
+                reason,
+            } => write!(
+                f,
+                "Invalid format \"{}\" at column {}: {}",
+                template, column, reason
+            ),
+            Error::UnknownRenderer { name, available } => write!(
+                f,
+                "There is no output format called \"{}\", expected one of {}",
+                name,
+                available.join(", ")
+            ),
+            Error::Output { format, .. } => {
+                write!(f, "Could not write the forecast as {}", format)
+            }
+            Error::Locations {
+                attempted,
+                failures,
+            } => {
+                write!(f, "{} of {} locations failed", failures.len(), attempted)?;
+                for (location, e) in failures {
+                    write!(f, "\n  {}: {}", location, e)?;
+                    let mut source = std::error::Error::source(e);
+                    while let Some(cause) = source {
+                        write!(f, ": {}", cause)?;
+                        source = cause.source();
+                    }
+                }
+                Ok(())
+            }
+            Error::Parse(_) => write!(f, "Could not parse the feed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Reqwest { source, .. } => Some(source),
+            Error::Client { source } => Some(source),
+            Error::Url { source, .. } => Some(source),
+            Error::Date { source, .. } => Some(source),
+            Error::Day { source, .. } => Some(source.as_ref()),
+            Error::Io { source, .. } => Some(source),
+            Error::ConfigSyntax { source, .. } => Some(source),
+            Error::Certificate { source, .. } => Some(source),
+            Error::Proxy { source, .. } => Some(source),
+            Error::Output { source, .. } => Some(source.as_ref()),
+            Error::Parse(e) => Some(e),
+            _ => None,
         }
     }
 }
 
-impl From<chrono::format::ParseError> for Error {
-    fn from(_: chrono::format::ParseError) -> Error {
-        Error::Conversion
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Error {
+        Error::Parse(e)
+    }
+}
+
+const SNIPPET_LEN: usize = 40;
+
+/// An error reading a feed, together with where in the document it
+/// happened.
+#[derive(Debug)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    path: String,
+    snippet: String,
+    cause: Cause,
+}
+
+#[derive(Debug)]
+enum Cause {
+    /// quick_xml could not read the XML.
+    Xml(quick_xml::Error),
+    /// An element ended without a child element the feed needs.
+    Missing(&'static str),
+}
+
+impl ParseError {
+    /// Builds an error for byte offset `position` of `body`. `path` is the
+    /// stack of open elements at that point, outermost first.
+    pub fn new(body: &str, position: usize, path: &[String], source: quick_xml::Error) -> Self {
+        ParseError::at(body, position, path, Cause::Xml(source))
+    }
+    /// Builds an error for the element `path` ending at byte offset
+    /// `position` of `body` without an `element` inside it.
+    pub fn missing(body: &str, position: usize, path: &[String], element: &'static str) -> Self {
+        ParseError::at(body, position, path, Cause::Missing(element))
+    }
+    fn at(body: &str, position: usize, path: &[String], cause: Cause) -> Self {
+        let mut position = position.min(body.len());
+        while !body.is_char_boundary(position) {
+            position -= 1;
+        }
+        let before = &body[..position];
+        let line = before.matches('\n').count() + 1;
+        let column = match before.rfind('\n') {
+            Some(index) => before[index + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+        let snippet = body[position..]
+            .chars()
+            .take_while(|c| *c != '\n')
+            .take(SNIPPET_LEN)
+            .collect();
+        ParseError {
+            line,
+            column,
+            path: format!("/{}", path.join("/")),
+            snippet,
+            cause,
+        }
+    }
+    pub fn line(&self) -> usize {
+        self.line
+    }
+    pub fn column(&self) -> usize {
+        self.column
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+    /// The element that was missing, if that is what went wrong.
+    pub fn missing_element(&self) -> Option<&str> {
+        match self.cause {
+            Cause::Missing(element) => Some(element),
+            Cause::Xml(_) => None,
+        }
     }
 }
 
-impl From<url::ParseError> for Error {
-    fn from(_: url::ParseError) -> Error {
-        Error::Conversion
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.cause {
+            Cause::Xml(_) => write!(
+                f,
+                "Invalid XML at line {}, column {} ({}) near \"{}\"",
+                self.line, self.column, self.path, self.snippet
+            ),
+            Cause::Missing(element) => write!(
+                f,
+                "Missing <{}> in {} ending at line {}, column {}",
+                element, self.path, self.line, self.column
+            ),
+        }
     }
 }
 
-impl From<reqwest::Error> for Error {
-    fn from(_: reqwest::Error) -> Error {
-        Error::Reqwest
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.cause {
+            Cause::Xml(source) => Some(source),
+            Cause::Missing(_) => None,
+        }
     }
 }
@@ -0,0 +1,242 @@
+use crate::error::Error;
+use crate::language::Language;
+pub use crate::net::HttpOptions;
+use crate::net::{build_client, fetch_with_retries};
+use crate::parser::Channel;
+use crate::{normalise_location, FeedKind, DEFAULT_BASE_URL};
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use url::{Position, Url};
+
+/// A raw RSS feed body and when it was fetched.
+#[derive(Clone, Debug)]
+pub struct Feed {
+    body: String,
+    fetched: DateTime<Utc>,
+    stale: bool,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    channel: Option<Channel>,
+}
+
+impl Feed {
+    /// A feed fetched just now.
+    pub fn new(body: String) -> Feed {
+        Feed {
+            body,
+            fetched: Utc::now(),
+            stale: false,
+            etag: None,
+            last_modified: None,
+            channel: None,
+        }
+    }
+    pub fn set_fetched(&mut self, fetched: DateTime<Utc>) {
+        self.fetched = fetched;
+    }
+    /// Marks the feed as an out of date copy served because a fresh one
+    /// could not be fetched.
+    pub fn set_stale(&mut self, stale: bool) {
+        self.stale = stale;
+    }
+    /// The ETag header the feed was served with.
+    pub fn set_etag(&mut self, etag: Option<String>) {
+        self.etag = etag;
+    }
+    /// The Last-Modified header the feed was served with.
+    pub fn set_last_modified(&mut self, last_modified: Option<String>) {
+        self.last_modified = last_modified;
+    }
+    /// Keeps the parsed body, for fetchers which had to parse it anyway, so
+    /// that it is not parsed a second time.
+    pub fn set_channel(&mut self, channel: Channel) {
+        self.channel = Some(channel);
+    }
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+    pub fn fetched(&self) -> DateTime<Utc> {
+        self.fetched
+    }
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+    /// The parsed body, if a fetcher has already parsed it.
+    pub fn channel(&self) -> Option<&Channel> {
+        self.channel.as_ref()
+    }
+    /// Takes the parsed body out of the feed, if there is one.
+    pub fn take_channel(&mut self) -> Option<Channel> {
+        self.channel.take()
+    }
+}
+
+/// A source of raw RSS feeds for a location segment.
+pub trait Fetcher {
+    fn fetch(&self, location: &str) -> Result<Feed, Error>;
+    /// Fetches `location` again given an older copy of its feed. Sources
+    /// which understand validators, such as ETags, can use them to avoid
+    /// sending an unchanged body twice.
+    fn revalidate(&self, location: &str, _cached: &Feed) -> Result<Feed, Error> {
+        self.fetch(location)
+    }
+    /// The key under which feeds for `location` are cached. Fetchers whose
+    /// feeds differ by more than the location, such as by language, should
+    /// fold that into the key.
+    fn cache_key(&self, location: &str) -> String {
+        String::from(location)
+    }
+}
+
+impl<F: Fetcher + ?Sized> Fetcher for &F {
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        (**self).fetch(location)
+    }
+    fn revalidate(&self, location: &str, cached: &Feed) -> Result<Feed, Error> {
+        (**self).revalidate(location, cached)
+    }
+    fn cache_key(&self, location: &str) -> String {
+        (**self).cache_key(location)
+    }
+}
+
+impl<F: Fetcher + ?Sized> Fetcher for Box<F> {
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        (**self).fetch(location)
+    }
+    fn revalidate(&self, location: &str, cached: &Feed) -> Result<Feed, Error> {
+        (**self).revalidate(location, cached)
+    }
+    fn cache_key(&self, location: &str) -> String {
+        (**self).cache_key(location)
+    }
+}
+
+/// Fetches the feed from BBC Weather over blocking HTTP.
+pub struct HttpFetcher {
+    client: reqwest::blocking::Client,
+    options: HttpOptions,
+}
+
+impl HttpFetcher {
+    pub fn new(options: HttpOptions) -> Result<HttpFetcher, Error> {
+        Ok(HttpFetcher {
+            client: build_client(&options)?,
+            options,
+        })
+    }
+    pub fn options(&self) -> &HttpOptions {
+        &self.options
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        let url = self.options.feed_url(location)?;
+        fetch_with_retries(&self.client, &url, &self.options, None)
+    }
+    fn revalidate(&self, location: &str, cached: &Feed) -> Result<Feed, Error> {
+        let url = self.options.feed_url(location)?;
+        fetch_with_retries(&self.client, &url, &self.options, Some(cached))
+    }
+    fn cache_key(&self, location: &str) -> String {
+        let mut key = normalise_location(location).unwrap_or_else(|_| String::from(location));
+        if self.options.feed_kind() == FeedKind::Observation {
+            key = format!("observation/{}", key);
+        }
+        key = match self.options.language() {
+            Language::English => key,
+            language => format!("{}/{}", language, key),
+        };
+        // Feeds from a mirror or stub server are kept apart from BBC
+        // Weather's, under the server's host and path.
+        let base_url = self.options.base_url();
+        if Url::parse(DEFAULT_BASE_URL).ok().as_ref() != Some(base_url) {
+            let server = &base_url[Position::BeforeHost..Position::AfterPath];
+            key = format!("{}/{}", server.trim_end_matches('/'), key);
+        }
+        key
+    }
+}
+
+/// Reads a previously saved feed from disk, whatever the location.
+pub struct FileFetcher {
+    path: PathBuf,
+}
+
+impl FileFetcher {
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileFetcher {
+        FileFetcher { path: path.into() }
+    }
+}
+
+impl Fetcher for FileFetcher {
+    fn fetch(&self, _location: &str) -> Result<Feed, Error> {
+        let body = std::fs::read_to_string(&self.path).map_err(|source| Error::Io {
+            path: self.path.display().to_string(),
+            source,
+        })?;
+        Ok(Feed::new(body))
+    }
+}
+
+/// Reads a feed piped to standard input, whatever the location.
+#[derive(Default)]
+pub struct StdinFetcher;
+
+impl Fetcher for StdinFetcher {
+    fn fetch(&self, _location: &str) -> Result<Feed, Error> {
+        let mut body = String::new();
+        std::io::stdin()
+            .read_to_string(&mut body)
+            .map_err(|source| Error::Io {
+                path: String::from("<stdin>"),
+                source,
+            })?;
+        Ok(Feed::new(body))
+    }
+}
+
+/// Serves feeds held in memory, for tests and fixtures. Locations without a
+/// body of their own receive the fallback body, if one was set.
+#[derive(Default)]
+pub struct MemoryFetcher {
+    bodies: HashMap<String, String>,
+    fallback: Option<String>,
+}
+
+impl MemoryFetcher {
+    pub fn new() -> MemoryFetcher {
+        MemoryFetcher::default()
+    }
+    pub fn with_fallback(body: &str) -> MemoryFetcher {
+        MemoryFetcher {
+            bodies: HashMap::new(),
+            fallback: Some(String::from(body)),
+        }
+    }
+    pub fn insert(&mut self, location: &str, body: &str) {
+        self.bodies
+            .insert(String::from(location), String::from(body));
+    }
+}
+
+impl Fetcher for MemoryFetcher {
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        match self.bodies.get(location).or(self.fallback.as_ref()) {
+            Some(body) => Ok(Feed::new(body.clone())),
+            None => Err(Error::NotFound {
+                location: String::from(location),
+            }),
+        }
+    }
+}
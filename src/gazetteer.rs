@@ -0,0 +1,371 @@
+//! An offline index of well known places and their GeoNames ids, which are
+//! the location segments BBC Weather expects. The data is bundled into the
+//! binary from `data/gazetteer.tsv`, so searching never touches the network.
+//!
+//! Coverage is deliberately small: the larger towns and cities of the UK and
+//! Ireland, and capitals and major cities elsewhere, about 140 places in all.
+//! Anywhere else can still be fetched by its GeoNames id, and `nearest`
+//! reports how far away the place it settles on is so callers can tell when
+//! that is too far to be useful.
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::error::Error;
+
+const DATA: &str = include_str!("../data/gazetteer.tsv");
+
+/// A place in the gazetteer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    id: u32,
+    name: String,
+    country: String,
+    admin: String,
+    latitude: f64,
+    longitude: f64,
+    population: u64,
+}
+
+impl Place {
+    /// The GeoNames id, usable as a location segment.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// The ISO 3166 country code, such as "GB".
+    pub fn country(&self) -> &str {
+        &self.country
+    }
+    /// The first level administrative area, such as "Scotland" or "Oregon".
+    pub fn admin(&self) -> &str {
+        &self.admin
+    }
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+    pub fn population(&self) -> u64 {
+        self.population
+    }
+    fn parse(line: &str) -> Option<Place> {
+        let mut fields = line.split('\t');
+        let place = Place {
+            id: fields.next()?.parse().ok()?,
+            name: String::from(fields.next()?),
+            country: String::from(fields.next()?),
+            admin: String::from(fields.next()?),
+            latitude: fields.next()?.parse().ok()?,
+            longitude: fields.next()?.parse().ok()?,
+            population: fields.next()?.parse().ok()?,
+        };
+        match fields.next() {
+            Some(_) => None,
+            None => Some(place),
+        }
+    }
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}, {} ({})",
+            self.name, self.admin, self.country, self.id
+        )
+    }
+}
+
+/// How closely a place's name matched a search, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// The whole name, ignoring case and punctuation.
+    Exact,
+    /// The leading words of the name, as "Newcastle" is of "Newcastle upon
+    /// Tyne".
+    Words,
+    /// The start of the name.
+    Prefix,
+    /// Somewhere inside the name.
+    Substring,
+    /// A name within a few typing mistakes of the search.
+    Fuzzy,
+}
+
+/// A search result.
+#[derive(Debug, Clone, Copy)]
+pub struct Match {
+    place: &'static Place,
+    kind: MatchKind,
+}
+
+impl Match {
+    pub fn place(&self) -> &'static Place {
+        self.place
+    }
+    pub fn kind(&self) -> MatchKind {
+        self.kind
+    }
+}
+
+/// Every place in the gazetteer.
+pub fn places() -> &'static [Place] {
+    static PLACES: OnceLock<Vec<Place>> = OnceLock::new();
+    PLACES.get_or_init(|| {
+        DATA.lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                Place::parse(line).unwrap_or_else(|| panic!("Malformed gazetteer line: {}", line))
+            })
+            .collect()
+    })
+}
+
+/// Finds the places whose names resemble `query`, best matches first and
+/// the most populous first among equally good matches.
+///
+/// A query of the form "Perth, AU" or "Portland, Oregon" keeps only places
+/// whose country code or admin area matches the part after the comma.
+pub fn search(query: &str) -> Vec<Match> {
+    let (name, qualifier) = match query.rfind(',') {
+        Some(index) => (&query[..index], Some(fold(&query[index + 1..]))),
+        None => (query, None),
+    };
+    let name = fold(name);
+    if name.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<Match> = places()
+        .iter()
+        .filter(|place| match &qualifier {
+            Some(qualifier) => {
+                fold(&place.country) == *qualifier || fold(&place.admin) == *qualifier
+            }
+            None => true,
+        })
+        .filter_map(|place| match_kind(&name, &fold(&place.name)).map(|kind| Match { place, kind }))
+        .collect();
+    matches.sort_by_key(|m| (m.kind, Reverse(m.place.population)));
+    matches
+}
+
+/// Resolves `query` to a single place.
+///
+/// Names that match exactly, or that are the leading words of a longer
+/// name, are preferred; otherwise the closest looser matches are used. If
+/// more than one place remains the query is ambiguous and
+/// `Error::AmbiguousPlace` lists the candidates.
+pub fn resolve(query: &str) -> Result<&'static Place, Error> {
+    let matches = search(query);
+    let best = match matches.first() {
+        Some(m) => m.kind.max(MatchKind::Words),
+        None => return Err(Error::UnknownPlace(String::from(query))),
+    };
+    let candidates: Vec<&Match> = matches.iter().filter(|m| m.kind <= best).collect();
+    match candidates.as_slice() {
+        [m] => Ok(m.place),
+        _ => Err(Error::AmbiguousPlace {
+            query: String::from(query),
+            candidates: candidates.iter().map(|m| m.place.clone()).collect(),
+        }),
+    }
+}
+
+fn match_kind(query: &str, name: &str) -> Option<MatchKind> {
+    if name == query {
+        Some(MatchKind::Exact)
+    } else if let Some(rest) = name.strip_prefix(query) {
+        if rest.starts_with(' ') {
+            Some(MatchKind::Words)
+        } else {
+            Some(MatchKind::Prefix)
+        }
+    } else if name.contains(query) {
+        Some(MatchKind::Substring)
+    } else if query.chars().count() >= 4 {
+        // One mistake per four characters, so "Edinbrugh" finds Edinburgh
+        // while short names must be spelt exactly.
+        let allowed = query.chars().count() / 4;
+        let distance = levenshtein(query, name).min(
+            // Also forgive mistakes in the start of a longer name.
+            levenshtein(
+                query,
+                &name.chars().take(query.chars().count()).collect::<String>(),
+            ),
+        );
+        if distance <= allowed {
+            Some(MatchKind::Fuzzy)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+/// Lower cases `s` and turns hyphens, dots and apostrophes into spaces, so
+/// "stoke on trent" matches "Stoke-on-Trent".
+fn fold(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '-' | '.' | '\'' => ' ',
+            c => c,
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Mean radius of the Earth in kilometres.
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// The place closest to some coordinates, found by `nearest`.
+#[derive(Debug, Clone, Copy)]
+pub struct Nearest {
+    place: &'static Place,
+    distance_km: f64,
+}
+
+impl Nearest {
+    pub fn place(&self) -> &'static Place {
+        self.place
+    }
+    /// The great circle distance from the coordinates to the place.
+    pub fn distance_km(&self) -> f64 {
+        self.distance_km
+    }
+    /// Whether the place is within `NEARBY_KM` of the coordinates, and so
+    /// likely to share their weather.
+    pub fn is_nearby(&self) -> bool {
+        self.distance_km <= NEARBY_KM
+    }
+}
+
+/// How far a place may be from some coordinates and still stand in for them.
+pub const NEARBY_KM: f64 = 50.0;
+
+/// Finds the place nearest to `latitude` and `longitude`, in degrees.
+pub fn nearest(latitude: f64, longitude: f64) -> Option<Nearest> {
+    let target = to_cartesian(latitude, longitude);
+    let tree = kd_tree();
+    let mut best: Option<(usize, f64)> = None;
+    search_nearest(tree, 0, &target, &mut best);
+    best.map(|(index, _)| {
+        let place = &places()[index];
+        Nearest {
+            place,
+            distance_km: haversine_km(latitude, longitude, place.latitude, place.longitude),
+        }
+    })
+}
+
+/// A point on the unit sphere and the index of its place. Straight line
+/// distances between these points order places the same way as distances
+/// over the surface, and have no trouble at the poles or the antimeridian.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    point: [f64; 3],
+    index: usize,
+}
+
+/// A k-d tree stored as a slice: each subtree's root is its median element,
+/// split on the axis given by its depth, with its halves either side.
+fn kd_tree() -> &'static [Node] {
+    static TREE: OnceLock<Vec<Node>> = OnceLock::new();
+    TREE.get_or_init(|| {
+        let mut nodes: Vec<Node> = places()
+            .iter()
+            .enumerate()
+            .map(|(index, place)| Node {
+                point: to_cartesian(place.latitude, place.longitude),
+                index,
+            })
+            .collect();
+        build_kd_tree(&mut nodes, 0);
+        nodes
+    })
+}
+
+fn build_kd_tree(nodes: &mut [Node], depth: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let median = nodes.len() / 2;
+    nodes.select_nth_unstable_by(median, |a, b| a.point[axis].total_cmp(&b.point[axis]));
+    let (left, right) = nodes.split_at_mut(median);
+    build_kd_tree(left, depth + 1);
+    build_kd_tree(&mut right[1..], depth + 1);
+}
+
+fn search_nearest(
+    nodes: &[Node],
+    depth: usize,
+    target: &[f64; 3],
+    best: &mut Option<(usize, f64)>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+    let median = nodes.len() / 2;
+    let node = &nodes[median];
+    let distance: f64 = node
+        .point
+        .iter()
+        .zip(target)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum();
+    if best.map_or(true, |(_, d)| distance < d) {
+        *best = Some((node.index, distance));
+    }
+    let axis = depth % 3;
+    let offset = target[axis] - node.point[axis];
+    let (near, far) = if offset < 0.0 {
+        (&nodes[..median], &nodes[median + 1..])
+    } else {
+        (&nodes[median + 1..], &nodes[..median])
+    };
+    search_nearest(near, depth + 1, target, best);
+    if best.map_or(true, |(_, d)| offset * offset < d) {
+        search_nearest(far, depth + 1, target, best);
+    }
+}
+
+fn to_cartesian(latitude: f64, longitude: f64) -> [f64; 3] {
+    let (latitude, longitude) = (latitude.to_radians(), longitude.to_radians());
+    [
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    ]
+}
+
+fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let half_dlat = (lat2 - lat1) / 2.0;
+    let half_dlon = (lon2 - lon1).to_radians() / 2.0;
+    let a = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
@@ -0,0 +1,205 @@
+//! Pictures of the weather, drawn in ASCII as wttr.in does or as a single
+//! emoji, for each `ConditionKind`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::ConditionKind;
+
+/// How conditions are pictured, if at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Icons {
+    /// No pictures.
+    #[default]
+    None,
+    /// Five lines of ASCII art.
+    Ascii,
+    /// One emoji.
+    Emoji,
+}
+
+impl Icons {
+    /// The lines of the icon for `kind`, all the same display width: 13
+    /// columns for ASCII art and 2 for an emoji. Some emoji need a variation
+    /// selector to be drawn as emoji, so pad by `UnicodeWidthStr::width`
+    /// rather than by characters. Unknown conditions get a question mark.
+    pub fn icon(&self, kind: ConditionKind) -> &'static [&'static str] {
+        match self {
+            Icons::None => &[],
+            Icons::Ascii => ascii(kind),
+            Icons::Emoji => std::slice::from_ref(emoji(kind)),
+        }
+    }
+}
+
+impl std::str::FromStr for Icons {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Icons::None),
+            "ascii" => Ok(Icons::Ascii),
+            "emoji" => Ok(Icons::Emoji),
+            _ => Err(format!(
+                "\"{}\" is not a kind of icons, expected none, ascii or emoji",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Icons {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Icons::None => write!(f, "none"),
+            Icons::Ascii => write!(f, "ascii"),
+            Icons::Emoji => write!(f, "emoji"),
+        }
+    }
+}
+
+fn ascii(kind: ConditionKind) -> &'static [&'static str] {
+    match kind {
+        ConditionKind::Clear => &[
+            r"    \   /    ",
+            r"     .-.     ",
+            r"  - (   ) -  ",
+            r"     `-'     ",
+            r"    /   \    ",
+        ],
+        ConditionKind::PartlyCloudy => &[
+            r"   \  /      ",
+            r#" _ /"".-.    "#,
+            r"   \_(   ).  ",
+            r"   /(___(__) ",
+            r"             ",
+        ],
+        ConditionKind::Cloudy => &[
+            r"             ",
+            r"     .--.    ",
+            r"  .-(    ).  ",
+            r" (___.__)__) ",
+            r"             ",
+        ],
+        ConditionKind::Fog => &[
+            r"             ",
+            r" _ - _ - _ - ",
+            r"  _ - _ - _  ",
+            r" _ - _ - _ - ",
+            r"             ",
+        ],
+        ConditionKind::Drizzle => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"    ' ' ' '  ",
+            r"   ' ' ' '   ",
+        ],
+        ConditionKind::Rain => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"  ,','.','.  ",
+            r"  ,','.','.  ",
+        ],
+        ConditionKind::Sleet => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"    ' * ' *  ",
+            r"   * ' * '   ",
+        ],
+        ConditionKind::Snow => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"    *  *  *  ",
+            r"   *  *  *   ",
+        ],
+        ConditionKind::Hail => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"    o o o o  ",
+            r"   o o o o   ",
+        ],
+        ConditionKind::Thunder => &[
+            r"     .-.     ",
+            r"    (   ).   ",
+            r"   (___(__)  ",
+            r"    ,/_,'/_  ",
+            r"    ,'/,'/   ",
+        ],
+        ConditionKind::Unknown => &[
+            r"    .-.      ",
+            r"     __)     ",
+            r"    (        ",
+            r"     `-'     ",
+            r"      .      ",
+        ],
+    }
+}
+
+fn emoji(kind: ConditionKind) -> &'static &'static str {
+    match kind {
+        ConditionKind::Clear => &"☀️",
+        ConditionKind::PartlyCloudy => &"⛅",
+        ConditionKind::Cloudy => &"☁️",
+        ConditionKind::Fog => &"🌫️",
+        ConditionKind::Drizzle => &"🌦️",
+        ConditionKind::Rain => &"🌧️",
+        ConditionKind::Sleet => &"🌨️",
+        ConditionKind::Snow => &"❄️",
+        ConditionKind::Hail => &"🧊",
+        ConditionKind::Thunder => &"⛈️",
+        ConditionKind::Unknown => &"❔",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    const KINDS: [ConditionKind; 11] = [
+        ConditionKind::Clear,
+        ConditionKind::PartlyCloudy,
+        ConditionKind::Cloudy,
+        ConditionKind::Fog,
+        ConditionKind::Drizzle,
+        ConditionKind::Rain,
+        ConditionKind::Sleet,
+        ConditionKind::Snow,
+        ConditionKind::Hail,
+        ConditionKind::Thunder,
+        ConditionKind::Unknown,
+    ];
+
+    #[test]
+    fn ascii_icons_are_five_lines_of_one_width() {
+        for kind in KINDS {
+            let icon = Icons::Ascii.icon(kind);
+            assert_eq!(icon.len(), 5, "{:?}", kind);
+            for line in icon {
+                assert!(line.is_ascii(), "{:?}: {:?}", kind, line);
+                assert_eq!(line.width(), 13, "{:?}: {:?}", kind, line);
+            }
+        }
+    }
+
+    #[test]
+    fn emoji_are_one_line_two_columns_wide() {
+        for kind in KINDS {
+            let icon = Icons::Emoji.icon(kind);
+            assert_eq!(icon.len(), 1, "{:?}", kind);
+            assert_eq!(icon[0].width(), 2, "{:?}: {:?}", kind, icon[0]);
+        }
+    }
+
+    #[test]
+    fn no_icons_are_empty() {
+        for kind in KINDS {
+            assert!(Icons::None.icon(kind).is_empty());
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// The languages BBC Weather publishes its feeds in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en", alias = "english")]
+    English,
+    #[serde(rename = "cy", alias = "welsh")]
+    Welsh,
+    #[serde(rename = "gd", alias = "gaelic")]
+    Gaelic,
+}
+
+impl Language {
+    /// The code used in the feed path, e.g. "cy" in `/cy/forecast/...`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Welsh => "cy",
+            Language::Gaelic => "gd",
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "cy" | "welsh" => Ok(Language::Welsh),
+            "gd" | "gaelic" => Ok(Language::Gaelic),
+            _ => Err(format!(
+                "\"{}\" is not a BBC Weather language, expected en, cy or gd",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
@@ -1,50 +1,329 @@
+pub mod cache;
+mod client;
+pub use client::{Client, DEFAULT_CONCURRENCY};
+pub mod config;
 pub mod dayoption;
+pub mod document;
 use dayoption::Day;
-mod error;
+pub mod error;
 use error::Error;
-mod model;
-use model::Forecast;
+pub mod fetcher;
+use fetcher::{Feed, Fetcher};
+pub mod gazetteer;
+pub mod icon;
+pub mod language;
+use language::Language;
+pub mod model;
+use model::{Daily, Forecast, Observation};
 mod net;
-use net::reqwest_fetch_url;
-mod parser;
-use parser::parse_document;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod output;
+pub mod parser;
+pub mod prose;
+use parser::Channel;
+pub mod render;
+pub mod statusbar;
+pub mod style;
+pub mod table;
+pub mod template;
+pub mod units;
 
-const URL_PART: &str = "https://weather-broker-cdn.api.bbci.co.uk/en/forecast/rss/3day";
+use url::Url;
 
-fn build_url(segment: &str) -> Result<url::Url, Error> {
-    if segment.is_empty() || segment.len() > 128 {
-        return Err(Error::InvalidSegment);
+/// Where BBC Weather serves its feeds from.
+pub const DEFAULT_BASE_URL: &str = "https://weather-broker-cdn.api.bbci.co.uk";
+
+/// The feeds BBC Weather publishes for each location.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FeedKind {
+    /// The three day forecast.
+    #[default]
+    Forecast,
+    /// The latest observation from the nearest weather station.
+    Observation,
+}
+
+impl FeedKind {
+    fn path(&self) -> &'static [&'static str] {
+        match self {
+            FeedKind::Forecast => &["forecast", "rss", "3day"],
+            FeedKind::Observation => &["observation", "rss"],
+        }
     }
-    Ok(url::Url::parse(&format!("{}/{}", URL_PART, segment))?)
 }
 
-pub fn run(uri: &str, day_option: Option<Day>) -> Result<(), Error> {
-    let url = build_url(uri)?;
+const MAX_GEONAMES_ID_LEN: usize = 10;
 
-    let rss_body = reqwest_fetch_url(url)?;
+/// Checks that `location` is a numeric GeoNames id, such as "2643743", or a
+/// UK postcode district, such as "SW1A", and returns it trimmed and upper
+/// cased.
+pub fn normalise_location(location: &str) -> Result<String, Error> {
+    let normalised = location.trim().to_ascii_uppercase();
+    if is_geonames_id(&normalised) || is_postcode_district(&normalised) {
+        Ok(normalised)
+    } else {
+        Err(Error::InvalidLocation(String::from(location)))
+    }
+}
 
-    let parsed = parse_document(rss_body.as_str())?;
+/// Like `normalise_location`, but anything that is not a location id or
+/// postcode district is looked up as a place name in the gazetteer.
+pub fn resolve_location(location: &str) -> Result<String, Error> {
+    match normalise_location(location) {
+        Ok(location) => Ok(location),
+        Err(_) => Ok(gazetteer::resolve(location)?.id().to_string()),
+    }
+}
+
+fn is_geonames_id(location: &str) -> bool {
+    !location.is_empty()
+        && location.len() <= MAX_GEONAMES_ID_LEN
+        && location.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Outward codes take the forms A9, A99, A9A, AA9, AA99 and AA9A.
+fn is_postcode_district(location: &str) -> bool {
+    let bytes = location.as_bytes();
+    let letters = bytes.iter().take_while(|b| b.is_ascii_uppercase()).count();
+    if letters == 0 || letters > 2 {
+        return false;
+    }
+    match &bytes[letters..] {
+        [digit] => digit.is_ascii_digit(),
+        [digit, last] => digit.is_ascii_digit() && last.is_ascii_alphanumeric(),
+        _ => false,
+    }
+}
 
-    let forecast = Forecast::parse_from_items(parsed.get_items())?;
-    if day_option.is_some() {
-        let day_option = day_option.unwrap();
-        let forecast = match day_option {
-            Day::Today => forecast.one()?,
-            Day::Tomorrow => forecast.two()?,
-            Day::Overmorrow => forecast.three()?,
+/// The feed url for `segment`, such as
+/// `{base}/{language}/forecast/rss/3day/{segment}` for a forecast or
+/// `{base}/{language}/observation/rss/{segment}` for an observation. Any path
+/// on `base` is kept, so mirrors may live below the server root.
+pub(crate) fn build_url(
+    base: &Url,
+    language: Language,
+    kind: FeedKind,
+    segment: &str,
+) -> Result<Url, Error> {
+    let segment = normalise_location(segment)?;
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| Error::InvalidBaseUrl(base.clone()))?
+        .pop_if_empty()
+        .push(language.code())
+        .extend(kind.path())
+        .push(&segment);
+    Ok(url)
+}
+
+/// Finds the bundled location nearest to `latitude` and `longitude`, in
+/// degrees, along with its distance.
+pub fn nearest_location(latitude: f64, longitude: f64) -> Result<gazetteer::Nearest, Error> {
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(Error::InvalidCoordinates {
+            latitude,
+            longitude,
+        });
+    }
+    Ok(gazetteer::nearest(latitude, longitude).expect("the gazetteer has places"))
+}
+
+/// A location's forecast, parsed from its feed, with the feed's metadata.
+#[derive(Debug)]
+pub struct LocationForecast {
+    location: String,
+    feed: Feed,
+    channel: Channel,
+    forecast: Forecast,
+    day: Option<Day>,
+    days: Vec<usize>,
+}
+
+impl LocationForecast {
+    /// Parses the forecast `feed` fetched for `location`, keeping only
+    /// `day` if one is given. Fails with `Error::DayNotForecast` if the
+    /// forecast does not reach `day`.
+    pub fn new(
+        location: &str,
+        mut feed: Feed,
+        day: Option<Day>,
+    ) -> Result<LocationForecast, Error> {
+        let channel = parse_feed(&mut feed)?;
+        let forecast = Forecast::parse_from_items(channel.get_items())?;
+        let available = (&forecast).into_iter().count();
+        let days = match &day {
+            Some(day) => {
+                let issued = channel.get_pub_date().date_naive();
+                let index = day
+                    .index(issued, available)
+                    .ok_or_else(|| Error::DayNotForecast {
+                        day: day.to_string(),
+                        issued,
+                        days: available,
+                    })?;
+                vec![index]
+            }
+            None => (0..available).collect(),
         };
-        println!("{}", day_option);
-        println!("{}", forecast.summary());
-        println!("{}", forecast.details());
-    } else {
-        for (day, name) in forecast
-            .into_iter()
-            .zip(["Today", "Tomorrow", "Overmorrow"].iter())
-        {
-            println!("{}", name);
-            println!("{}", day.summary());
-            println!("{}", day.details());
+        Ok(LocationForecast {
+            location: String::from(location),
+            feed,
+            channel,
+            forecast,
+            day,
+            days,
+        })
+    }
+    /// The location as it was asked for.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+    /// The feed, with when it was fetched and whether it is stale.
+    pub fn feed(&self) -> &Feed {
+        &self.feed
+    }
+    /// The feed's title, link, language, copyright and publication date.
+    pub fn channel(&self) -> &Channel {
+        &self.channel
+    }
+    /// Every day of the forecast, whichever were chosen.
+    pub fn forecast(&self) -> &Forecast {
+        &self.forecast
+    }
+    /// The day that was chosen, if any.
+    pub fn day(&self) -> Option<Day> {
+        self.day
+    }
+    /// The chosen days, each with how many days after the forecast was
+    /// issued it is.
+    pub fn days(&self) -> impl Iterator<Item = (usize, &Daily)> + '_ {
+        self.days
+            .iter()
+            .filter_map(move |&index| Some((index, self.forecast.day(index).ok()?)))
+    }
+}
+
+/// A location's latest observation, parsed from its feed, with the feed's
+/// metadata.
+#[derive(Debug)]
+pub struct LocationObservation {
+    location: String,
+    feed: Feed,
+    channel: Channel,
+    observation: Observation,
+}
+
+impl LocationObservation {
+    /// Parses the observation `feed` fetched for `location`.
+    pub fn new(location: &str, mut feed: Feed) -> Result<LocationObservation, Error> {
+        let channel = parse_feed(&mut feed)?;
+        let observation = Observation::parse_from_items(channel.get_items())?;
+        Ok(LocationObservation {
+            location: String::from(location),
+            feed,
+            channel,
+            observation,
+        })
+    }
+    /// The location as it was asked for.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+    pub fn feed(&self) -> &Feed {
+        &self.feed
+    }
+    pub fn channel(&self) -> &Channel {
+        &self.channel
+    }
+    pub fn observation(&self) -> &Observation {
+        &self.observation
+    }
+}
+
+/// The channel a fetcher already parsed from `feed`, or else `feed` parsed
+/// now.
+pub(crate) fn parse_feed(feed: &mut Feed) -> Result<Channel, Error> {
+    match feed.take_channel() {
+        Some(channel) => Ok(channel),
+        None => parser::parse_document(feed.body()),
+    }
+}
+
+/// What `run` or `observe` got for each location: those that succeeded, in
+/// the order they were asked for, and those that failed.
+#[derive(Debug)]
+pub struct Outcome<T> {
+    attempted: usize,
+    successes: Vec<T>,
+    failures: Vec<(String, Error)>,
+}
+
+impl<T> Outcome<T> {
+    pub fn successes(&self) -> &[T] {
+        &self.successes
+    }
+    /// The locations that failed, with why.
+    pub fn failures(&self) -> &[(String, Error)] {
+        &self.failures
+    }
+    /// The successes, and an error if any location failed: a single
+    /// failure as is, several as `Error::Locations`.
+    pub fn into_parts(mut self) -> (Vec<T>, Result<(), Error>) {
+        let result = match self.failures.len() {
+            0 => Ok(()),
+            1 if self.attempted == 1 => Err(self.failures.remove(0).1),
+            _ => Err(Error::Locations {
+                attempted: self.attempted,
+                failures: self.failures,
+            }),
+        };
+        (self.successes, result)
+    }
+    /// The successes, or an error if any location failed.
+    pub fn into_result(self) -> Result<Vec<T>, Error> {
+        let (successes, result) = self.into_parts();
+        result.map(|_| successes)
+    }
+}
+
+/// Fetches and parses the forecast for each location, keeping only `day`
+/// if one is given. Every location is attempted even if some fail.
+pub fn run<F: Fetcher + Sync>(
+    client: &Client<F>,
+    locations: &[&str],
+    day: Option<Day>,
+) -> Outcome<LocationForecast> {
+    each_feed(client, locations, |location, feed| {
+        LocationForecast::new(location, feed, day)
+    })
+}
+
+/// Fetches and parses the latest observation for each location, as `run`
+/// does for forecasts. `client` must fetch observation feeds, see
+/// `HttpOptions::set_feed_kind`.
+pub fn observe<F: Fetcher + Sync>(
+    client: &Client<F>,
+    locations: &[&str],
+) -> Outcome<LocationObservation> {
+    each_feed(client, locations, LocationObservation::new)
+}
+
+fn each_feed<F, T, P>(client: &Client<F>, locations: &[&str], parse: P) -> Outcome<T>
+where
+    F: Fetcher + Sync,
+    P: Fn(&str, Feed) -> Result<T, Error>,
+{
+    let mut outcome = Outcome {
+        attempted: locations.len(),
+        successes: Vec::new(),
+        failures: Vec::new(),
+    };
+    for (location, feed) in locations.iter().zip(client.feeds(locations)) {
+        match feed.and_then(|feed| parse(location, feed)) {
+            Ok(parsed) => outcome.successes.push(parsed),
+            Err(e) => outcome.failures.push((String::from(*location), e)),
         }
     }
-    Ok(())
+    outcome
 }
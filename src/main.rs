@@ -1,49 +1,803 @@
+use text_weather::cache::{Cache, CachingFetcher, DEFAULT_TTL};
+use text_weather::config::Config;
 use text_weather::dayoption::Day;
+use text_weather::fetcher::{Fetcher, FileFetcher, HttpFetcher, HttpOptions, StdinFetcher};
+use text_weather::gazetteer;
+use text_weather::icon::Icons;
+use text_weather::output::OutputFormat;
+use text_weather::prose::{Prose, Ssml};
+use text_weather::render::{self, RenderOptions, Renderers};
+use text_weather::style::{ColorChoice, Palette};
+use text_weather::table::Borders;
+use text_weather::template::{self, Template};
+use text_weather::{Client, FeedKind};
 
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+
+/// The flags `forecast` took before it was a subcommand, still accepted on
+/// their own.
+const DEPRECATED_DAY_FLAGS: [(&str, &str); 3] = [
+    ("today", "today"),
+    ("tomorrow", "tomorrow"),
+    ("overmorrow", "overmorrow"),
+];
 
 fn main() {
+    let format_help = template::help();
     let matches = App::new("text_weather")
+        .about("BBC Weather forecasts and observations")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
-            Arg::with_name("segment")
-                .long("segment")
-                .required(true)
-                .takes_value(true),
+            Arg::with_name("config")
+                .long("config")
+                .help("Read settings from this file [default: ~/.config/text_weather/config.toml]")
+                .takes_value(true)
+                .global(true),
         )
-        .arg(
-            Arg::with_name("today")
-                .long("today")
-                .required(false)
-                .takes_value(false),
+        // Running without a subcommand is a deprecated spelling of
+        // `forecast`, so its arguments are accepted here too, unlisted.
+        .args(&hidden(forecast_args(&format_help)))
+        .groups(&forecast_groups())
+        .subcommand(
+            SubCommand::with_name("forecast")
+                .about("Show the three day forecast")
+                .args(&forecast_args(&format_help))
+                .groups(&forecast_groups()),
         )
-        .arg(
-            Arg::with_name("tomorrow")
-                .long("tomorrow")
-                .required(false)
-                .takes_value(false),
+        .subcommand(
+            SubCommand::with_name("observe")
+                .about("Show the latest observation from the nearest weather station")
+                .args(&feed_args())
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .help("How to write the observation [default: text]")
+                        .takes_value(true)
+                        .possible_values(&["text", "json", "ndjson", "csv", "yaml"]),
+                )
+                .group(location_group()),
         )
-        .arg(
-            Arg::with_name("overmorrow")
-                .long("overmorrow")
-                .required(false)
-                .takes_value(false),
+        .subcommand(
+            SubCommand::with_name("locations")
+                .about("Look up location ids without going online")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("search")
+                        .about("Find places by name, most populous first")
+                        .arg(Arg::with_name("name").required(true))
+                        .arg(
+                            Arg::with_name("limit")
+                                .long("limit")
+                                .help("Show at most this many places [default: 10]")
+                                .takes_value(true)
+                                .validator(is_number),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("nearest")
+                        .about("Find the place nearest to a latitude and longitude")
+                        .args(&coordinate_args(true)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .about("Inspect or clear cached feeds")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(SubCommand::with_name("list").about("List cached feeds"))
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Show a cached feed")
+                        .arg(Arg::with_name("location").required(true))
+                        .arg(
+                            Arg::with_name("body")
+                                .long("body")
+                                .help("Print the raw feed instead of its metadata"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("clear")
+                        .about("Remove one cached feed, or all of them")
+                        .arg(Arg::with_name("location")),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Check the config file")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("show").about("Print the settings in the config file"),
+                )
+                .subcommand(
+                    SubCommand::with_name("validate")
+                        .about("Check that every setting in the config file can be used"),
+                ),
         )
         .get_matches();
 
-    let segment = matches.value_of("segment").unwrap();
-    let mut day_option: Option<Day> = None;
-    if matches.is_present("today") {
-        day_option.replace(Day::Today);
-    } else if matches.is_present("tomorrow") {
-        day_option.replace(Day::Tomorrow);
-    } else if matches.is_present("overmorrow") {
-        day_option.replace(Day::Overmorrow);
+    let explicit_config = matches.value_of("config").is_some();
+    let config_path = match matches.value_of("config") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Config::default_path(),
+    };
+    let config = || match load_config(config_path.as_ref(), explicit_config) {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => exit_with_error(e),
+    };
+    match matches.subcommand() {
+        ("forecast", Some(matches)) => run_feeds(matches, &config(), FeedKind::Forecast),
+        ("observe", Some(matches)) => run_feeds(matches, &config(), FeedKind::Observation),
+        ("locations", Some(matches)) => run_locations(matches),
+        ("cache", Some(matches)) => {
+            if let Err(e) = run_cache(matches) {
+                exit_with_error(e);
+            }
+        }
+        ("config", Some(matches)) => run_config(matches, config_path, explicit_config),
+        _ => {
+            if ["locations", "days", "input"]
+                .iter()
+                .any(|name| matches.is_present(name))
+            {
+                eprintln!(
+                    "warning: options without a subcommand are deprecated, \
+                     use `text_weather forecast` followed by the same options"
+                );
+            }
+            run_feeds(&matches, &config(), FeedKind::Forecast)
+        }
+    }
+}
+
+/// What `forecast` accepts: everything `observe` does, plus a day.
+fn forecast_args<'a, 'b>(format_help: &'b str) -> Vec<Arg<'a, 'b>> {
+    let mut args = feed_args();
+    args.push(
+        Arg::with_name("day")
+            .long("day")
+            .help("Only show one day: today, tomorrow, overmorrow, a weekday or a date such as 2026-10-20")
+            .takes_value(true)
+            .validator(is_day),
+    );
+    args.push(
+        Arg::with_name("output")
+            .long("output")
+            .help("How to write the forecast [default: text]")
+            .takes_value(true)
+            .possible_values(&[
+                "text", "compact", "verbose", "table", "prose", "ssml", "markdown", "html", "json",
+                "ndjson", "csv", "yaml", "waybar", "i3bar", "tmux",
+            ]),
+    );
+    args.push(
+        Arg::with_name("table")
+            .long("table")
+            .help("Show the days side by side in a table; the same as --output table")
+            .conflicts_with_all(&["output", "format"]),
+    );
+    args.push(
+        Arg::with_name("icons")
+            .long("icons")
+            .help("Picture each day's weather in text and tables [default: ascii on a terminal]")
+            .takes_value(true)
+            .possible_values(&["ascii", "emoji"]),
+    );
+    args.push(
+        Arg::with_name("no-icons")
+            .long("no-icons")
+            .help("Leave out the pictures of each day's weather")
+            .conflicts_with("icons"),
+    );
+    args.push(
+        Arg::with_name("ascii")
+            .long("ascii")
+            .help("Draw tables with ASCII rather than box-drawing characters"),
+    );
+    args.push(
+        Arg::with_name("format")
+            .long("format")
+            .help("Write a line for each day from a template such as \"{day}: {condition}, {min}-{max}\"")
+            .long_help(format_help)
+            .takes_value(true)
+            .conflicts_with("output"),
+    );
+    for (flag, _) in DEPRECATED_DAY_FLAGS.iter() {
+        args.push(Arg::with_name(flag).long(flag).hidden(true));
+    }
+    args
+}
+
+fn forecast_groups<'a>() -> Vec<ArgGroup<'a>> {
+    let mut days = vec!["day"];
+    days.extend(DEPRECATED_DAY_FLAGS.iter().map(|(flag, _)| *flag));
+    vec![location_group(), ArgGroup::with_name("days").args(&days)]
+}
+
+/// The locations may be combined, but not with a saved feed.
+fn location_group<'a>() -> ArgGroup<'a> {
+    ArgGroup::with_name("locations")
+        .args(&["segment", "location", "lat"])
+        .multiple(true)
+        .conflicts_with("input")
+}
+
+/// Where to fetch a feed for and how.
+fn feed_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    let mut args = vec![
+        Arg::with_name("segment")
+            .long("segment")
+            .help("A location id or alias; repeat to fetch several locations")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+        Arg::with_name("location")
+            .long("location")
+            .help("A place name such as \"Bristol\" or \"Perth, AU\", or an alias; may be repeated")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+        Arg::with_name("input")
+            .long("input")
+            .help("Read a saved feed from a file, or - for stdin, instead of fetching it")
+            .takes_value(true)
+            .conflicts_with("concurrency"),
+        Arg::with_name("concurrency")
+            .long("concurrency")
+            .help("How many locations to fetch at once [default: 8]")
+            .takes_value(true)
+            .validator(is_number),
+        Arg::with_name("units")
+            .long("units")
+            .help("Show temperatures and wind speeds in these units [default: both]")
+            .takes_value(true)
+            .possible_values(&["metric", "imperial", "both"]),
+        Arg::with_name("color")
+            .long("color")
+            .help("Colour text output; auto colours it when writing to a terminal and NO_COLOR is not set [default: auto]")
+            .takes_value(true)
+            .possible_values(&["auto", "always", "never"]),
+        Arg::with_name("base-url")
+            .long("base-url")
+            .help("Fetch feeds from this server instead of BBC Weather")
+            .takes_value(true)
+            .validator(is_url),
+        Arg::with_name("language")
+            .long("language")
+            .help("The feed language")
+            .takes_value(true)
+            .possible_values(&["en", "cy", "gd"]),
+        Arg::with_name("user-agent")
+            .long("user-agent")
+            .help("The User-Agent header to send")
+            .takes_value(true),
+        Arg::with_name("proxy")
+            .long("proxy")
+            .help("Send HTTP and HTTPS requests through this proxy")
+            .takes_value(true)
+            .validator(is_url),
+        Arg::with_name("ca-cert")
+            .long("ca-cert")
+            .help("Also trust this PEM encoded CA certificate; may be repeated")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+        Arg::with_name("connect-timeout")
+            .long("connect-timeout")
+            .help("Seconds to wait for a connection to BBC Weather")
+            .takes_value(true)
+            .validator(is_seconds),
+        Arg::with_name("timeout")
+            .long("timeout")
+            .help("Seconds to allow each request to BBC Weather, including reading the feed")
+            .takes_value(true)
+            .validator(is_seconds),
+        Arg::with_name("retries")
+            .long("retries")
+            .help("Times to retry connection failures, 5xx and 429 responses")
+            .takes_value(true)
+            .validator(is_number),
+        Arg::with_name("cache-ttl")
+            .long("cache-ttl")
+            .help("Seconds to serve a cached feed before fetching it again [default: 1800]")
+            .takes_value(true)
+            .validator(is_number),
+        Arg::with_name("no-cache")
+            .long("no-cache")
+            .help("Always fetch the feed and leave the cache untouched")
+            .conflicts_with("cache-ttl"),
+    ];
+    args.extend(coordinate_args(false));
+    args
+}
+
+/// --lat and --lon, which must be given together.
+fn coordinate_args<'a, 'b>(required: bool) -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("lat")
+            .long("lat")
+            .help("Use the location nearest to this latitude, in degrees")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .required(required)
+            .requires("lon")
+            .validator(is_coordinate),
+        Arg::with_name("lon")
+            .long("lon")
+            .help("Use the location nearest to this longitude, in degrees")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .required(required)
+            .requires("lat")
+            .validator(is_coordinate),
+    ]
+}
+
+fn hidden<'a, 'b>(args: Vec<Arg<'a, 'b>>) -> Vec<Arg<'a, 'b>> {
+    args.into_iter().map(|arg| arg.hidden(true)).collect()
+}
+
+/// Fetches a feed of `kind` for each location given in `matches`, or for
+/// the configured default location, and prints it.
+fn run_feeds(matches: &ArgMatches, config: &Config, kind: FeedKind) {
+    let input = matches.value_of("input");
+    let mut locations = Vec::new();
+    for segment in matches.values_of("segment").into_iter().flatten() {
+        match config.alias(segment) {
+            Some(alias) => match text_weather::resolve_location(alias) {
+                Ok(location) => locations.push(location),
+                Err(e) => exit_with_error(e),
+            },
+            None => locations.push(String::from(segment)),
+        }
+    }
+    for name in matches.values_of("location").into_iter().flatten() {
+        match config.location(name) {
+            Ok(location) => locations.push(location),
+            Err(e) => exit_with_error(e),
+        }
+    }
+    if let Some(location) = nearest(matches) {
+        locations.push(location);
+    }
+    if locations.is_empty() {
+        match (input, config.default_location()) {
+            (Some(input), _) => locations.push(String::from(input)),
+            (None, Some(default)) => match config.location(default) {
+                Ok(location) => locations.push(location),
+                Err(e) => exit_with_error(e),
+            },
+            (None, None) => {
+                eprintln!(
+                    "error: No location given; use --segment, --location, --lat and --lon, \
+                     or set default_location in the config file\n\n\
+                     For more information try --help"
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    let locations: Vec<&str> = locations.iter().map(String::as_str).collect();
+    let fetcher: Box<dyn Fetcher + Send + Sync> = match input {
+        Some("-") => Box::new(StdinFetcher),
+        Some(path) => Box::new(FileFetcher::new(path)),
+        None => {
+            let mut options = match config.http_options() {
+                Ok(options) => http_options(options, matches),
+                Err(e) => exit_with_error(e),
+            };
+            options.set_feed_kind(kind);
+            let fetcher = match HttpFetcher::new(options) {
+                Ok(fetcher) => fetcher,
+                Err(e) => exit_with_error(e),
+            };
+            match Cache::default_dir() {
+                Some(dir) if !matches.is_present("no-cache") => {
+                    let ttl = match matches.value_of("cache-ttl") {
+                        Some(seconds) => Duration::from_secs(seconds.parse().unwrap()),
+                        None => config.cache_ttl().unwrap_or(DEFAULT_TTL),
+                    };
+                    Box::new(CachingFetcher::new(fetcher, Cache::new(dir), ttl))
+                }
+                _ => Box::new(fetcher),
+            }
+        }
+    };
+    let mut client = Client::new(fetcher);
+    if let Some(concurrency) = matches.value_of("concurrency") {
+        client.set_concurrency(concurrency.parse().unwrap());
+    }
+    let mut options = RenderOptions::default();
+    options.set_units(match matches.value_of("units") {
+        Some(units) => units.parse().unwrap(),
+        None => config.units().unwrap_or_default(),
+    });
+    let color: ColorChoice = match matches.value_of("color") {
+        Some(color) => color.parse().unwrap(),
+        None => config.color().unwrap_or_default(),
+    };
+    options.set_palette(Palette::new(color.enabled(io::stdout().is_terminal())));
+    options.set_icons(if matches.is_present("no-icons") {
+        Icons::None
+    } else {
+        match matches.value_of("icons") {
+            Some(icons) => icons.parse().unwrap(),
+            // Pictures are for people, so leave them out of piped output
+            // unless they were asked for.
+            None => config.icons().unwrap_or(if io::stdout().is_terminal() {
+                Icons::Ascii
+            } else {
+                Icons::None
+            }),
+        }
+    });
+    options.set_width(terminal_width());
+    options.set_borders(if matches.is_present("ascii") {
+        Borders::Ascii
+    } else {
+        Borders::detect()
+    });
+    let result = match kind {
+        FeedKind::Forecast => {
+            let mut renderers = Renderers::default();
+            let phrases = config.phrases();
+            renderers.register(
+                &OutputFormat::Prose.to_string(),
+                Prose::new(phrases.clone()),
+            );
+            renderers.register(&OutputFormat::Ssml.to_string(), Ssml::new(phrases));
+            let format = match matches.value_of("format") {
+                Some(format) => Some(format),
+                None if matches.is_present("output") || matches.is_present("table") => None,
+                None => config.format(),
+            };
+            let renderer = match format {
+                Some(format) => match Template::parse(format) {
+                    Ok(template) => {
+                        renderers.register("format", template);
+                        String::from("format")
+                    }
+                    Err(e) => exit_with_error(e),
+                },
+                None if matches.is_present("table") => OutputFormat::Table.to_string(),
+                None => match matches.value_of("output") {
+                    Some(output) => output.to_string(),
+                    None => config.output().unwrap_or_default().to_string(),
+                },
+            };
+            let (forecasts, result) =
+                text_weather::run(&client, &locations, day_option(matches)).into_parts();
+            renderers
+                .render(&renderer, io::stdout().lock(), &forecasts, &options)
+                .and(result)
+        }
+        FeedKind::Observation => {
+            let format = match matches.value_of("output") {
+                Some(output) => output.parse().unwrap(),
+                // The config's output may be one only forecasts have.
+                None => match config.output() {
+                    Some(
+                        output @ (OutputFormat::Json
+                        | OutputFormat::Ndjson
+                        | OutputFormat::Csv
+                        | OutputFormat::Yaml),
+                    ) => output,
+                    _ => OutputFormat::Text,
+                },
+            };
+            let (observations, result) = text_weather::observe(&client, &locations).into_parts();
+            render::observations(format, io::stdout().lock(), &observations, &options).and(result)
+        }
+    };
+    if let Err(e) = result {
+        exit_with_error(e);
+    }
+}
+
+/// The day chosen with --day or one of the deprecated day flags.
+fn day_option(matches: &ArgMatches) -> Option<Day> {
+    if let Some(day) = matches.value_of("day") {
+        return Some(day.parse().unwrap());
+    }
+    for (flag, day) in DEPRECATED_DAY_FLAGS.iter() {
+        if matches.is_present(flag) {
+            eprintln!("warning: --{} is deprecated, use --day {}", flag, day);
+            return Some(day.parse().unwrap());
+        }
+    }
+    None
+}
+
+/// The width of the terminal stdout is written to, or `COLUMNS` when it is
+/// piped and that is set.
+fn terminal_width() -> Option<usize> {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(width), _)) if io::stdout().is_terminal() => {
+            Some(usize::from(width))
+        }
+        _ => std::env::var("COLUMNS").ok()?.parse().ok(),
+    }
+}
+
+/// The location id nearest to --lat and --lon, if they were given.
+fn nearest(matches: &ArgMatches) -> Option<String> {
+    let (lat, lon) = (matches.value_of("lat")?, matches.value_of("lon")?);
+    match text_weather::nearest_location(lat.parse().unwrap(), lon.parse().unwrap()) {
+        Ok(nearest) => {
+            eprintln!(
+                "Nearest location: {}, {:.1} km away",
+                nearest.place(),
+                nearest.distance_km()
+            );
+            if !nearest.is_nearby() {
+                eprintln!(
+                    "The bundled gazetteer only knows larger towns and cities; use --segment \
+                     with a GeoNames id for somewhere closer"
+                );
+            }
+            Some(nearest.place().id().to_string())
+        }
+        Err(e) => exit_with_error(e),
+    }
+}
+
+fn run_locations(matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("search", Some(matches)) => {
+            let name = matches.value_of("name").unwrap();
+            let limit = match matches.value_of("limit") {
+                Some(limit) => limit.parse().unwrap(),
+                None => 10,
+            };
+            let found = gazetteer::search(name);
+            if found.is_empty() {
+                eprintln!("No known place matches \"{}\"", name);
+                std::process::exit(1);
+            }
+            for m in found.iter().take(limit) {
+                let place = m.place();
+                println!(
+                    "{}\t{}, {}, {}\t{:.4}, {:.4}\tpopulation {}",
+                    place.id(),
+                    place.name(),
+                    place.admin(),
+                    place.country(),
+                    place.latitude(),
+                    place.longitude(),
+                    place.population()
+                );
+            }
+        }
+        ("nearest", Some(matches)) => {
+            let (lat, lon) = (matches.value_of("lat"), matches.value_of("lon"));
+            match text_weather::nearest_location(
+                lat.unwrap().parse().unwrap(),
+                lon.unwrap().parse().unwrap(),
+            ) {
+                Ok(nearest) => println!(
+                    "{}\t{}, {}, {}\t{:.1} km away",
+                    nearest.place().id(),
+                    nearest.place().name(),
+                    nearest.place().admin(),
+                    nearest.place().country(),
+                    nearest.distance_km()
+                ),
+                Err(e) => exit_with_error(e),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Reads the config file, which must exist if it was named with --config.
+fn load_config(
+    path: Option<&PathBuf>,
+    explicit: bool,
+) -> Result<Option<Config>, text_weather::error::Error> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    match Config::load(path)? {
+        None if explicit => Err(text_weather::error::Error::Io {
+            path: path.display().to_string(),
+            source: std::io::ErrorKind::NotFound.into(),
+        }),
+        config => Ok(config),
+    }
+}
+
+fn run_config(matches: &ArgMatches, path: Option<PathBuf>, explicit: bool) {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            eprintln!("No config file: neither XDG_CONFIG_HOME nor HOME is set");
+            std::process::exit(1);
+        }
+    };
+    let config = match load_config(Some(&path), explicit) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("No config file at {}, using the defaults", path.display());
+            return;
+        }
+        Err(e) => exit_with_error(e),
+    };
+    match matches.subcommand() {
+        ("show", _) => {
+            println!("# {}", path.display());
+            match toml::to_string(&config) {
+                Ok(toml) => print!("{}", toml),
+                Err(e) => {
+                    eprintln!("Could not write the config as TOML: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        ("validate", _) => {
+            for (alias, location) in config.locations() {
+                let id = text_weather::resolve_location(location).unwrap();
+                println!("{} = {} ({})", alias, location, id);
+            }
+            println!("{} is valid", path.display());
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn run_cache(matches: &ArgMatches) -> Result<(), text_weather::error::Error> {
+    let cache = match Cache::default_dir() {
+        Some(dir) => Cache::new(dir),
+        None => {
+            eprintln!("No cache directory: neither XDG_CACHE_HOME nor HOME is set");
+            std::process::exit(1);
+        }
+    };
+    match matches.subcommand() {
+        ("list", _) => {
+            for entry in cache.list()? {
+                let published = match entry.pub_date() {
+                    Some(date) => date.to_rfc2822(),
+                    None => String::from("unknown"),
+                };
+                println!(
+                    "{}\tfetched {} ({} minutes ago)\tpublished {}",
+                    entry.location(),
+                    entry.fetched().format("%Y-%m-%d %H:%M UTC"),
+                    entry.age().num_minutes(),
+                    published
+                );
+            }
+        }
+        ("show", Some(matches)) => {
+            let location = matches.value_of("location").unwrap();
+            let entry = match cache.load(location)? {
+                Some(entry) => entry,
+                None => {
+                    eprintln!("Nothing is cached for {}", location);
+                    std::process::exit(1);
+                }
+            };
+            if matches.is_present("body") {
+                print!("{}", entry.body());
+            } else {
+                println!("Location: {}", entry.location());
+                println!("Fetched: {}", entry.fetched().to_rfc3339());
+                println!("Age: {} minutes", entry.age().num_minutes());
+                if let Some(date) = entry.pub_date() {
+                    println!("Published: {}", date.to_rfc2822());
+                }
+                if let Some(etag) = entry.etag() {
+                    println!("ETag: {}", etag);
+                }
+                if let Some(last_modified) = entry.last_modified() {
+                    println!("Last-Modified: {}", last_modified);
+                }
+                println!("Size: {} bytes", entry.body().len());
+            }
+        }
+        ("clear", Some(matches)) => match matches.value_of("location") {
+            Some(location) => {
+                if cache.remove(location)? {
+                    println!("Removed {}", location);
+                } else {
+                    println!("Nothing is cached for {}", location);
+                }
+            }
+            None => println!("Removed {} cached feed(s)", cache.clear()?),
+        },
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// `options` with any HTTP flags applied on top.
+fn http_options(mut options: HttpOptions, matches: &ArgMatches) -> HttpOptions {
+    if let Some(base_url) = matches.value_of("base-url") {
+        options.set_base_url(Url::parse(base_url).unwrap());
+    }
+    if let Some(language) = matches.value_of("language") {
+        options.set_language(language.parse().unwrap());
+    }
+    if let Some(user_agent) = matches.value_of("user-agent") {
+        options.set_user_agent(user_agent);
+    }
+    if let Some(proxy) = matches.value_of("proxy") {
+        options.set_proxy(Some(proxy));
+    }
+    for path in matches.values_of("ca-cert").into_iter().flatten() {
+        options.add_ca_certificate(path);
+    }
+    if let Some(seconds) = matches.value_of("connect-timeout") {
+        options.set_connect_timeout(Duration::from_secs(seconds.parse().unwrap()));
+    }
+    if let Some(seconds) = matches.value_of("timeout") {
+        options.set_timeout(Duration::from_secs(seconds.parse().unwrap()));
+    }
+    if let Some(retries) = matches.value_of("retries") {
+        options.set_retries(retries.parse().unwrap());
+    }
+    options
+}
+
+/// A timeout, which would fail every request if it were zero.
+fn is_seconds(value: String) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(0) => Err(String::from("expected at least one second")),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("\"{}\" is not a whole number", value)),
+    }
+}
+
+fn is_number(value: String) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("\"{}\" is not a whole number", value)),
     }
-    match text_weather::run(segment, day_option) {
-        Ok(_) => (),
-        Err(e) => {
-            eprintln!("{}", e);
-            panic!();
+}
+
+fn is_coordinate(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("\"{}\" is not a number of degrees", value)),
+    }
+}
+
+fn is_day(value: String) -> Result<(), String> {
+    value.parse::<Day>().map(|_| ())
+}
+
+fn is_url(value: String) -> Result<(), String> {
+    match Url::parse(&value) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("\"{}\" is not a valid url: {}", value, e)),
+    }
+}
+
+fn exit_with_error(e: text_weather::error::Error) -> ! {
+    if is_broken_pipe(&e) {
+        // Whatever was reading the output, such as head, has had enough.
+        std::process::exit(0);
+    }
+    eprintln!("Error: {}", e);
+    let mut source = e.source();
+    while let Some(cause) = source {
+        eprintln!("  caused by: {}", cause);
+        source = cause.source();
+    }
+    std::process::exit(1);
+}
+
+fn is_broken_pipe(e: &text_weather::error::Error) -> bool {
+    let mut source = e.source();
+    while let Some(cause) = source {
+        if let Some(e) = cause.downcast_ref::<io::Error>() {
+            return e.kind() == io::ErrorKind::BrokenPipe;
         }
+        source = cause.source();
     }
+    false
 }
@@ -1,12 +1,15 @@
 use crate::error::Error;
 use crate::parser::Item;
+use crate::units::Units;
 
 use std::fmt;
 
+#[derive(Debug)]
 pub struct Forecast {
     days: Vec<Daily>,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Forecast {
     fn default() -> Self {
         Forecast { days: Vec::new() }
@@ -16,31 +19,34 @@ impl Default for Forecast {
 impl Forecast {
     pub fn parse_from_items(items: &[Item]) -> Result<Forecast, Error> {
         let mut days: Vec<Daily> = Vec::new();
-        for item in items {
-            days.push(Daily::parse_from_item_title_and_description(
+        for (day, item) in items.iter().enumerate() {
+            let daily = Daily::parse_from_item_title_and_description(
                 item.get_title(),
                 item.get_description(),
-            )?);
+            )
+            .map_err(|e| Error::Day {
+                day,
+                source: Box::new(e),
+            })?;
+            days.push(daily);
         }
         Ok(Forecast { days })
     }
     pub fn one(&self) -> Result<&Daily, Error> {
-        if self.days.is_empty() {
-            return Err(Error::Conversion);
-        }
-        Ok(&self.days[0])
+        self.day(0)
     }
     pub fn two(&self) -> Result<&Daily, Error> {
-        if self.days.is_empty() || self.days.len() != 3 {
-            return Err(Error::Conversion);
-        }
-        Ok(&self.days[1])
+        self.day(1)
     }
     pub fn three(&self) -> Result<&Daily, Error> {
-        if self.days.is_empty() || self.days.len() != 3 {
-            return Err(Error::Conversion);
-        }
-        Ok(&self.days[2])
+        self.day(2)
+    }
+    /// The day `day` days after the forecast was issued.
+    pub fn day(&self, day: usize) -> Result<&Daily, Error> {
+        self.days.get(day).ok_or(Error::MissingDay {
+            day,
+            available: self.days.len(),
+        })
     }
 }
 
@@ -74,11 +80,13 @@ impl<'a> IntoIterator for &'a mut Forecast {
     }
 }
 
+#[derive(Debug)]
 pub struct Daily {
     summary: Summary,
     details: Details,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Daily {
     fn default() -> Self {
         Daily {
@@ -116,10 +124,12 @@ impl Daily {
     }
 }
 
+#[derive(Debug)]
 pub struct Summary {
     summary: String,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Summary {
     fn default() -> Self {
         Summary {
@@ -137,18 +147,22 @@ impl fmt::Display for Summary {
 impl Summary {
     pub fn parse_from_str(title: &str) -> Result<Summary, Error> {
         if title.is_empty() || title.len() > 1024 {
-            return Err(Error::Conversion);
+            return Err(conversion("summary", title, "expected 1 to 1024 bytes"));
         }
         let fields: Vec<&str> = title.split(',').collect();
         if fields.is_empty() || fields.len() != 2 {
-            return Err(Error::Conversion);
+            return Err(conversion(
+                "summary",
+                title,
+                "expected two comma separated fields",
+            ));
         }
         let (_, summary) = match fields[0].find(':') {
             Some(index) => ((), &fields[0][index..]),
-            None => return Err(Error::Conversion),
+            None => return Err(conversion("summary", title, "missing ':' separator")),
         };
         if summary.len() <= 2 {
-            return Err(Error::Conversion);
+            return Err(conversion("summary", title, "missing value"));
         }
         Ok(Summary {
             summary: String::from(&summary[2..]),
@@ -157,8 +171,85 @@ impl Summary {
     pub fn summary(&self) -> &str {
         &self.summary
     }
+    pub fn kind(&self) -> ConditionKind {
+        ConditionKind::from_condition(&self.summary)
+    }
+}
+
+/// The broad kind of weather a condition such as "Light Rain Showers"
+/// describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConditionKind {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Sleet,
+    Snow,
+    Hail,
+    Thunder,
+    Unknown,
+}
+
+impl ConditionKind {
+    /// Classifies a condition as BBC Weather words it, going by the most
+    /// severe weather it mentions.
+    pub fn from_condition(condition: &str) -> ConditionKind {
+        let condition = condition.to_lowercase();
+        let mentions = |word: &str| condition.contains(word);
+        if mentions("thunder") {
+            ConditionKind::Thunder
+        } else if mentions("hail") {
+            ConditionKind::Hail
+        } else if mentions("sleet") {
+            ConditionKind::Sleet
+        } else if mentions("snow") {
+            ConditionKind::Snow
+        } else if mentions("drizzle") {
+            ConditionKind::Drizzle
+        } else if mentions("rain") || mentions("shower") {
+            ConditionKind::Rain
+        } else if mentions("fog") || mentions("mist") || mentions("haz") {
+            ConditionKind::Fog
+        } else if mentions("intervals") || mentions("partly") || mentions("light cloud") {
+            ConditionKind::PartlyCloudy
+        } else if mentions("cloud") || mentions("overcast") {
+            ConditionKind::Cloudy
+        } else if mentions("sunny") || mentions("clear") {
+            ConditionKind::Clear
+        } else {
+            ConditionKind::Unknown
+        }
+    }
+    /// A lower case name such as "partly-cloudy", usable as a CSS class.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConditionKind::Clear => "clear",
+            ConditionKind::PartlyCloudy => "partly-cloudy",
+            ConditionKind::Cloudy => "cloudy",
+            ConditionKind::Fog => "fog",
+            ConditionKind::Drizzle => "drizzle",
+            ConditionKind::Rain => "rain",
+            ConditionKind::Sleet => "sleet",
+            ConditionKind::Snow => "snow",
+            ConditionKind::Hail => "hail",
+            ConditionKind::Thunder => "thunder",
+            ConditionKind::Unknown => "unknown",
+        }
+    }
 }
 
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub(crate) const KM_PER_MILE: f64 = 1.609344;
+
+#[derive(Clone, Debug)]
 pub struct Details {
     temperature_max: String,
     temperature_min: String,
@@ -173,6 +264,7 @@ pub struct Details {
     sunset_time: String,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Details {
     fn default() -> Self {
         Details {
@@ -215,19 +307,27 @@ impl Details {
         let mut details = Details::default();
 
         if description.is_empty() || description.len() > 4096 {
-            return Err(Error::Conversion);
+            return Err(conversion(
+                "details",
+                description,
+                "expected 1 to 4096 bytes",
+            ));
         }
         let fields: Vec<&str> = description.split(',').collect();
         if fields.is_empty() || fields.len() != 11 {
-            return Err(Error::Conversion);
+            return Err(conversion(
+                "details",
+                description,
+                "expected eleven comma separated fields",
+            ));
         }
         for field in fields {
             let (first, rest) = match field.find(':') {
                 Some(index) => (&field[0..index], &field[index..]),
-                None => return Err(Error::Conversion),
+                None => return Err(conversion("details", field, "missing ':' separator")),
             };
             if rest.len() <= 2 {
-                return Err(Error::Conversion);
+                return Err(conversion(first.trim(), field, "missing value"));
             }
             let field = match first.trim() {
                 "Maximum Temperature" => &mut details.temperature_max,
@@ -241,10 +341,269 @@ impl Details {
                 "Pollution" => &mut details.pollution_level,
                 "Sunrise" => &mut details.sunrise_time,
                 "Sunset" => &mut details.sunset_time,
-                _ => return Err(Error::Conversion),
+                _ => return Err(conversion("details", field, "unknown field")),
             };
             *field = String::from(&rest[2..]);
         }
         Ok(details)
     }
+    pub fn temperature_max(&self) -> &str {
+        &self.temperature_max
+    }
+    pub fn temperature_min(&self) -> &str {
+        &self.temperature_min
+    }
+    pub fn wind_direction(&self) -> &str {
+        &self.wind_direction
+    }
+    pub fn wind_speed(&self) -> &str {
+        &self.wind_speed
+    }
+    pub fn visibility(&self) -> &str {
+        &self.visibility
+    }
+    pub fn pressure(&self) -> &str {
+        &self.pressure
+    }
+    pub fn humidity(&self) -> &str {
+        &self.humidity
+    }
+    pub fn uv_risk(&self) -> &str {
+        &self.uv_risk
+    }
+    pub fn pollution_level(&self) -> &str {
+        &self.pollution_level
+    }
+    pub fn sunrise_time(&self) -> &str {
+        &self.sunrise_time
+    }
+    pub fn sunset_time(&self) -> &str {
+        &self.sunset_time
+    }
+    /// The typed values below are `None` when BBC Weather leaves a value
+    /// out, for example overnight maxima shown as "--°C".
+    pub fn temperature_max_celsius(&self) -> Option<i32> {
+        celsius(&self.temperature_max)
+    }
+    pub fn temperature_max_fahrenheit(&self) -> Option<i32> {
+        fahrenheit(&self.temperature_max)
+    }
+    pub fn temperature_min_celsius(&self) -> Option<i32> {
+        celsius(&self.temperature_min)
+    }
+    pub fn temperature_min_fahrenheit(&self) -> Option<i32> {
+        fahrenheit(&self.temperature_min)
+    }
+    pub fn wind_speed_mph(&self) -> Option<u32> {
+        number_before(&self.wind_speed, "mph")
+    }
+    pub fn pressure_mb(&self) -> Option<u32> {
+        number_before(&self.pressure, "mb")
+    }
+    pub fn humidity_percent(&self) -> Option<u32> {
+        number_before(&self.humidity, "%")
+    }
+    pub fn uv_risk_index(&self) -> Option<u32> {
+        self.uv_risk.trim().parse().ok()
+    }
+    /// A copy of these details with temperatures and wind speed in `units`.
+    /// Values not in the form BBC Weather publishes are left as they are.
+    pub fn in_units(&self, units: Units) -> Details {
+        Details {
+            temperature_max: temperature(&self.temperature_max, units),
+            temperature_min: temperature(&self.temperature_min, units),
+            wind_speed: wind_speed(&self.wind_speed, units),
+            ..self.clone()
+        }
+    }
+}
+
+/// The latest conditions reported by the weather station nearest a location.
+#[derive(Clone, Debug)]
+pub struct Observation {
+    observed: String,
+    condition: String,
+    temperature: String,
+    wind_direction: String,
+    wind_speed: String,
+    humidity: String,
+    pressure: String,
+    visibility: String,
+}
+
+impl fmt::Display for Observation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+            "Observed: {}\nCondition: {}\nTemperature: {}\nWind Direction: {}\nWind Speed: {}\nHumidity: {}\nPressure: {}\nVisibility: {}\n",
+            self.observed,
+            self.condition,
+            self.temperature,
+            self.wind_direction,
+            self.wind_speed,
+            self.humidity,
+            self.pressure,
+            self.visibility
+        )
+    }
+}
+
+impl Observation {
+    pub fn parse_from_items(items: &[Item]) -> Result<Observation, Error> {
+        match items.first() {
+            Some(item) => Observation::parse_from_item_title_and_description(
+                item.get_title(),
+                item.get_description(),
+            ),
+            None => Err(Error::NoObservation),
+        }
+    }
+    /// Reads a title such as "Sunday - 18:00 BST: Sunny, 15°C (59°F)" and a
+    /// description of comma separated "Name: value" fields. Stations leave
+    /// out what they do not measure, which is shown as "--".
+    pub fn parse_from_item_title_and_description(
+        title: &str,
+        description: &str,
+    ) -> Result<Observation, Error> {
+        let (observed, rest) = match title.split_once(": ") {
+            Some(parts) => parts,
+            None => return Err(conversion("observation", title, "missing ': ' separator")),
+        };
+        let condition = match rest.rsplit_once(", ") {
+            Some((condition, _)) => condition,
+            None => rest,
+        };
+        let mut observation = Observation {
+            observed: String::from(observed),
+            condition: String::from(condition),
+            temperature: String::from("--"),
+            wind_direction: String::from("--"),
+            wind_speed: String::from("--"),
+            humidity: String::from("--"),
+            pressure: String::from("--"),
+            visibility: String::from("--"),
+        };
+        // Pressure carries its tendency after a comma, as in "1012mb, Falling",
+        // so a piece without a name continues the previous value.
+        let mut previous: Option<&mut String> = None;
+        for piece in description.split(", ") {
+            let (name, value) = match piece.split_once(": ") {
+                Some(parts) => parts,
+                None => match previous {
+                    Some(ref mut value) => {
+                        value.push_str(", ");
+                        value.push_str(piece);
+                        continue;
+                    }
+                    None => return Err(conversion("observation", piece, "missing ': ' separator")),
+                },
+            };
+            let field = match name.trim() {
+                "Temperature" => &mut observation.temperature,
+                "Wind Direction" => &mut observation.wind_direction,
+                "Wind Speed" => &mut observation.wind_speed,
+                "Humidity" => &mut observation.humidity,
+                "Pressure" => &mut observation.pressure,
+                "Visibility" => &mut observation.visibility,
+                _ => return Err(conversion("observation", piece, "unknown field")),
+            };
+            *field = String::from(value.trim());
+            previous = Some(field);
+        }
+        Ok(observation)
+    }
+    pub fn observed(&self) -> &str {
+        &self.observed
+    }
+    pub fn condition(&self) -> &str {
+        &self.condition
+    }
+    pub fn condition_kind(&self) -> ConditionKind {
+        ConditionKind::from_condition(&self.condition)
+    }
+    pub fn temperature(&self) -> &str {
+        &self.temperature
+    }
+    pub fn wind_direction(&self) -> &str {
+        &self.wind_direction
+    }
+    pub fn wind_speed(&self) -> &str {
+        &self.wind_speed
+    }
+    pub fn humidity(&self) -> &str {
+        &self.humidity
+    }
+    pub fn pressure(&self) -> &str {
+        &self.pressure
+    }
+    pub fn visibility(&self) -> &str {
+        &self.visibility
+    }
+    pub fn temperature_celsius(&self) -> Option<i32> {
+        celsius(&self.temperature)
+    }
+    pub fn temperature_fahrenheit(&self) -> Option<i32> {
+        fahrenheit(&self.temperature)
+    }
+    pub fn wind_speed_mph(&self) -> Option<u32> {
+        number_before(&self.wind_speed, "mph")
+    }
+    pub fn humidity_percent(&self) -> Option<u32> {
+        number_before(&self.humidity, "%")
+    }
+    /// The pressure without its tendency, from a value such as "1012mb,
+    /// Falling".
+    pub fn pressure_mb(&self) -> Option<u32> {
+        number_before(self.pressure.split(',').next()?, "mb")
+    }
+    /// A copy of this observation with temperature and wind speed in
+    /// `units`.
+    pub fn in_units(&self, units: Units) -> Observation {
+        Observation {
+            temperature: temperature(&self.temperature, units),
+            wind_speed: wind_speed(&self.wind_speed, units),
+            ..self.clone()
+        }
+    }
+}
+
+fn celsius(temperature: &str) -> Option<i32> {
+    number_before(temperature.split(" (").next()?, "°C")
+}
+
+fn fahrenheit(temperature: &str) -> Option<i32> {
+    let (_, fahrenheit) = temperature.split_once(" (")?;
+    number_before(fahrenheit.trim_end_matches(')'), "°F")
+}
+
+/// The number in a value such as "1012mb", given its unit suffix.
+fn number_before<T: std::str::FromStr>(value: &str, unit: &str) -> Option<T> {
+    value.trim().strip_suffix(unit)?.trim().parse().ok()
+}
+
+/// Picks one scale out of a temperature such as "15°C (59°F)".
+fn temperature(value: &str, units: Units) -> String {
+    match (units, value.split_once(" (")) {
+        (Units::Metric, Some((celsius, _))) => String::from(celsius),
+        (Units::Imperial, Some((_, fahrenheit))) => String::from(fahrenheit.trim_end_matches(')')),
+        _ => String::from(value),
+    }
+}
+
+/// Converts a wind speed such as "12mph" to kilometres per hour for metric.
+fn wind_speed(value: &str, units: Units) -> String {
+    let mph = value
+        .strip_suffix("mph")
+        .and_then(|mph| mph.trim().parse::<f64>().ok());
+    match (units, mph) {
+        (Units::Metric, Some(mph)) => format!("{:.0}km/h", mph * KM_PER_MILE),
+        _ => String::from(value),
+    }
+}
+
+fn conversion(field: &str, value: &str, reason: &'static str) -> Error {
+    Error::Conversion {
+        field: String::from(field),
+        value: String::from(value),
+        reason,
+    }
 }
@@ -1,10 +1,434 @@
 use crate::error::Error;
+use crate::fetcher::Feed;
+use crate::language::Language;
+use crate::{build_url, FeedKind, DEFAULT_BASE_URL};
 
-use reqwest::blocking::get;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Certificate, Proxy, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
 use url::Url;
 
-pub fn reqwest_fetch_url(url: Url) -> Result<String, Error> {
-    let response = get(url)?;
-    let text = response.text()?;
-    Ok(text)
+/// Where feeds are fetched from and how: server, language, identity,
+/// timeouts and retries.
+#[derive(Clone, Debug)]
+pub struct HttpOptions {
+    base_url: Url,
+    language: Language,
+    feed_kind: FeedKind,
+    user_agent: String,
+    proxy: Option<String>,
+    ca_certificates: Vec<PathBuf>,
+    connect_timeout: Duration,
+    timeout: Duration,
+    retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        HttpOptions {
+            base_url: Url::parse(DEFAULT_BASE_URL).unwrap(),
+            language: Language::default(),
+            feed_kind: FeedKind::default(),
+            user_agent: format!("text_weather/{}", env!("CARGO_PKG_VERSION")),
+            proxy: None,
+            ca_certificates: Vec::new(),
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HttpOptions {
+    /// Serve feeds from somewhere other than BBC Weather, such as a mirror
+    /// or a local stub server.
+    pub fn set_base_url(&mut self, base_url: Url) {
+        self.base_url = base_url;
+    }
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+    /// Fetch forecasts, the default, or observations.
+    pub fn set_feed_kind(&mut self, feed_kind: FeedKind) {
+        self.feed_kind = feed_kind;
+    }
+    pub fn set_user_agent(&mut self, user_agent: &str) {
+        self.user_agent = String::from(user_agent);
+    }
+    /// Sends both HTTP and HTTPS requests through `proxy`. Without one the
+    /// usual proxy environment variables are honoured.
+    pub fn set_proxy(&mut self, proxy: Option<&str>) {
+        self.proxy = proxy.map(String::from);
+    }
+    /// Trusts the PEM encoded certificate at `path` in addition to the
+    /// system roots.
+    pub fn add_ca_certificate<P: Into<PathBuf>>(&mut self, path: P) {
+        self.ca_certificates.push(path.into());
+    }
+    pub fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.connect_timeout = timeout;
+    }
+    /// The longest a whole request may take, from connecting to reading
+    /// the last of the body. Each retry gets this long again.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+    pub fn set_retries(&mut self, retries: u32) {
+        self.retries = retries;
+    }
+    pub fn set_initial_backoff(&mut self, backoff: Duration) {
+        self.initial_backoff = backoff;
+    }
+    pub fn set_max_backoff(&mut self, backoff: Duration) {
+        self.max_backoff = backoff;
+    }
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+    pub fn language(&self) -> Language {
+        self.language
+    }
+    pub fn feed_kind(&self) -> FeedKind {
+        self.feed_kind
+    }
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+    pub fn ca_certificates(&self) -> &[PathBuf] {
+        &self.ca_certificates
+    }
+    /// The feed url for the location `segment`.
+    pub fn feed_url(&self, segment: &str) -> Result<Url, Error> {
+        build_url(&self.base_url, self.language, self.feed_kind, segment)
+    }
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+}
+
+pub fn build_client(options: &HttpOptions) -> Result<Client, Error> {
+    let mut builder = Client::builder()
+        .user_agent(options.user_agent.as_str())
+        .connect_timeout(options.connect_timeout)
+        .timeout(options.timeout);
+    if let Some(proxy) = build_proxy(options)? {
+        builder = builder.proxy(proxy);
+    }
+    for certificate in load_certificates(options)? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder.build().map_err(|source| Error::Client { source })
+}
+
+fn build_proxy(options: &HttpOptions) -> Result<Option<Proxy>, Error> {
+    match &options.proxy {
+        Some(proxy) => Proxy::all(proxy.as_str())
+            .map(Some)
+            .map_err(|source| Error::Proxy {
+                proxy: proxy.clone(),
+                source,
+            }),
+        None => Ok(None),
+    }
+}
+
+fn load_certificates(options: &HttpOptions) -> Result<Vec<Certificate>, Error> {
+    let mut certificates = Vec::new();
+    for path in &options.ca_certificates {
+        let pem = std::fs::read(path).map_err(|source| Error::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let certificate = Certificate::from_pem(&pem).map_err(|source| Error::Certificate {
+            path: path.display().to_string(),
+            source,
+        })?;
+        certificates.push(certificate);
+    }
+    Ok(certificates)
+}
+
+/// Fetches `url`, retrying transient failures with exponential backoff.
+/// When `cached` is given its validators are sent along, and a 304 response
+/// yields the cached body again.
+pub fn fetch_with_retries(
+    client: &Client,
+    url: &Url,
+    options: &HttpOptions,
+    cached: Option<&Feed>,
+) -> Result<Feed, Error> {
+    let mut attempt = 0;
+    loop {
+        let result = reqwest_fetch_url(client, url.clone(), cached);
+        match next_retry(&result, options, attempt) {
+            Some(delay) => {
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            None => return result,
+        }
+    }
+}
+
+pub fn reqwest_fetch_url(client: &Client, url: Url, cached: Option<&Feed>) -> Result<Feed, Error> {
+    let response = client
+        .get(url.clone())
+        .headers(conditional_headers(cached))
+        .send()
+        .map_err(|source| Error::Reqwest {
+            url: url.clone(),
+            source,
+        })?;
+    let headers = response.headers().clone();
+    let feed = match not_modified(response.status(), cached) {
+        Some(feed) => feed,
+        None => {
+            check_response(&url, response.status(), &headers)?;
+            let text = response
+                .text()
+                .map_err(|source| Error::Reqwest { url, source })?;
+            Feed::new(text)
+        }
+    };
+    Ok(with_validators(feed, &headers))
+}
+
+#[cfg(feature = "async")]
+pub fn build_async_client(options: &HttpOptions) -> Result<reqwest::Client, Error> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(options.user_agent.as_str())
+        .connect_timeout(options.connect_timeout)
+        .timeout(options.timeout);
+    if let Some(proxy) = build_proxy(options)? {
+        builder = builder.proxy(proxy);
+    }
+    for certificate in load_certificates(options)? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder.build().map_err(|source| Error::Client { source })
+}
+
+/// The async counterpart of `fetch_with_retries`.
+#[cfg(feature = "async")]
+pub async fn fetch_with_retries_async(
+    client: &reqwest::Client,
+    url: &Url,
+    options: &HttpOptions,
+    cached: Option<&Feed>,
+) -> Result<Feed, Error> {
+    let mut attempt = 0;
+    loop {
+        let result = reqwest_fetch_url_async(client, url.clone(), cached).await;
+        match next_retry(&result, options, attempt) {
+            Some(delay) => {
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            None => return result,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+async fn reqwest_fetch_url_async(
+    client: &reqwest::Client,
+    url: Url,
+    cached: Option<&Feed>,
+) -> Result<Feed, Error> {
+    let response = client
+        .get(url.clone())
+        .headers(conditional_headers(cached))
+        .send()
+        .await
+        .map_err(|source| Error::Reqwest {
+            url: url.clone(),
+            source,
+        })?;
+    let headers = response.headers().clone();
+    let feed = match not_modified(response.status(), cached) {
+        Some(feed) => feed,
+        None => {
+            check_response(&url, response.status(), &headers)?;
+            let text = response
+                .text()
+                .await
+                .map_err(|source| Error::Reqwest { url, source })?;
+            Feed::new(text)
+        }
+    };
+    Ok(with_validators(feed, &headers))
+}
+
+/// If-None-Match and If-Modified-Since from the validators of `cached`.
+fn conditional_headers(cached: Option<&Feed>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(cached) = cached {
+        if let Some(etag) = cached.etag().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = cached
+            .last_modified()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    headers
+}
+
+/// A fresh copy of `cached` if the server answered 304 Not Modified.
+fn not_modified(status: StatusCode, cached: Option<&Feed>) -> Option<Feed> {
+    match cached {
+        Some(cached) if status == StatusCode::NOT_MODIFIED => {
+            let mut feed = Feed::new(String::from(cached.body()));
+            feed.set_etag(cached.etag().map(String::from));
+            feed.set_last_modified(cached.last_modified().map(String::from));
+            Some(feed)
+        }
+        _ => None,
+    }
+}
+
+/// A 304 may carry updated validators; otherwise keep the ones we had.
+fn with_validators(mut feed: Feed, headers: &HeaderMap) -> Feed {
+    if let Some(etag) = header(headers, ETAG) {
+        feed.set_etag(Some(etag));
+    }
+    if let Some(last_modified) = header(headers, LAST_MODIFIED) {
+        feed.set_last_modified(Some(last_modified));
+    }
+    feed
+}
+
+fn header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+/// Whether `error` is worth trying again later: the server could not be
+/// reached, timed out, or said it is overloaded.
+pub(crate) fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Reqwest { source, .. } => {
+            source.is_connect() || source.is_timeout() || source.is_request() || source.is_body()
+        }
+        Error::ServerError { .. } | Error::RateLimited { .. } => true,
+        _ => false,
+    }
+}
+
+/// How long to wait before trying again after `result`, or `None` if it
+/// should be returned as it is.
+fn next_retry(
+    result: &Result<Feed, Error>,
+    options: &HttpOptions,
+    attempt: u32,
+) -> Option<Duration> {
+    match result {
+        Err(e) if attempt < options.retries && is_transient(e) => retry_delay(e, options, attempt),
+        _ => None,
+    }
+}
+
+/// Honours Retry-After when the server sent one, giving up rather than
+/// retrying early if it asks for longer than `max_backoff`. Otherwise backs
+/// off exponentially from `initial_backoff`, capped at `max_backoff`, with up
+/// to half the delay in jitter so that many clients do not retry in lockstep.
+fn retry_delay(error: &Error, options: &HttpOptions, attempt: u32) -> Option<Duration> {
+    if let Error::RateLimited {
+        retry_after: Some(delay),
+        ..
+    } = error
+    {
+        return Some(*delay).filter(|delay| *delay <= options.max_backoff);
+    }
+    let delay = options
+        .initial_backoff
+        .checked_mul(2u32.saturating_pow(attempt))
+        .unwrap_or(options.max_backoff)
+        .min(options.max_backoff);
+    let half = delay / 2;
+    let jitter = RandomState::new().build_hasher().finish() % (half.as_millis() as u64 + 1);
+    Some(half + Duration::from_millis(jitter))
+}
+
+/// Maps an unsuccessful status, or a body which cannot be an RSS feed, onto
+/// the matching `Error` before the body is read.
+fn check_response(url: &Url, status: StatusCode, headers: &HeaderMap) -> Result<(), Error> {
+    if status == StatusCode::NOT_FOUND {
+        let location = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default();
+        return Err(Error::NotFound {
+            location: String::from(location),
+        });
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = header(headers, RETRY_AFTER).and_then(|value| parse_retry_after(&value));
+        return Err(Error::RateLimited {
+            url: url.clone(),
+            retry_after,
+        });
+    }
+    if status.is_server_error() {
+        return Err(Error::ServerError {
+            url: url.clone(),
+            status: status.as_u16(),
+        });
+    }
+    if !status.is_success() {
+        return Err(Error::UnexpectedStatus {
+            url: url.clone(),
+            status: status.as_u16(),
+        });
+    }
+    if let Some(content_type) = header(headers, CONTENT_TYPE) {
+        if !content_type.contains("xml") {
+            return Err(Error::UnexpectedContentType {
+                url: url.clone(),
+                content_type,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Retry-After is either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
 }
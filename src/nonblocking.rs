@@ -0,0 +1,44 @@
+//! An async counterpart of `Client`, enabled by the `async` feature. It
+//! fetches over HTTP on the caller's tokio runtime and shares the feed
+//! parsing with the blocking client.
+
+use crate::client::{parse_forecast, parse_observation};
+use crate::error::Error;
+use crate::fetcher::{Feed, HttpOptions};
+use crate::model::{Forecast, Observation};
+use crate::net::{build_async_client, fetch_with_retries_async};
+
+pub struct Client {
+    client: reqwest::Client,
+    options: HttpOptions,
+}
+
+impl Client {
+    pub fn new(options: HttpOptions) -> Result<Client, Error> {
+        Ok(Client {
+            client: build_async_client(&options)?,
+            options,
+        })
+    }
+    pub fn options(&self) -> &HttpOptions {
+        &self.options
+    }
+    pub async fn feed(&self, location: &str) -> Result<Feed, Error> {
+        let url = self.options.feed_url(location)?;
+        fetch_with_retries_async(&self.client, &url, &self.options, None).await
+    }
+    /// Fetches `location` again, sending the validators of `cached` so an
+    /// unchanged feed is not downloaded twice.
+    pub async fn revalidate(&self, location: &str, cached: &Feed) -> Result<Feed, Error> {
+        let url = self.options.feed_url(location)?;
+        fetch_with_retries_async(&self.client, &url, &self.options, Some(cached)).await
+    }
+    pub async fn forecast(&self, location: &str) -> Result<Forecast, Error> {
+        parse_forecast(self.feed(location).await?)
+    }
+    /// The latest observation for `location`, for a client whose options
+    /// fetch observation feeds.
+    pub async fn observation(&self, location: &str) -> Result<Observation, Error> {
+        parse_observation(self.feed(location).await?)
+    }
+}
@@ -0,0 +1,516 @@
+//! Output formats, and forecasts as JSON, NDJSON, CSV or YAML for scripts
+//! to consume.
+//!
+//! The output follows a versioned schema, given as `schema_version` in
+//! every document and row. Fields may be added within a version; removing
+//! or renaming a field, or changing its type, means a new version.
+//!
+//! JSON and YAML hold one document for the whole run:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "forecasts": [
+//!     {
+//!       "location": {"id": "2643743", "name": "London, GB",
+//!                    "link": "https://www.bbc.co.uk/weather/2643743", "language": "en"},
+//!       "issued": "2026-10-18T05:00:00+00:00",
+//!       "fetched": "2026-10-18T06:12:09+00:00",
+//!       "stale": false,
+//!       "days": [{"index": 0, "date": "2026-10-18", "weekday": "Sunday",
+//!                 "condition": "Sunny Intervals", "temperature_max_c": 15, ...}]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! NDJSON and CSV hold one flat row per location and day, with the location
+//! fields prefixed `location_`. CSV starts with a header even when there
+//! are no rows. Times are RFC 3339 and dates ISO 8601. Typed values BBC
+//! Weather left out are null in JSON and YAML and empty in CSV.
+//!
+//! Observations follow the same schema, with `observations` in place of
+//! `forecasts` and one row per location, since each has a single
+//! observation rather than days.
+
+use std::io::{self, Write};
+
+use chrono::{Duration, NaiveDate, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::model::Daily;
+use crate::{LocationForecast, LocationObservation};
+
+/// The version of the structured output schema described above.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How forecasts are written out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Labelled lines for people to read.
+    #[default]
+    Text,
+    /// One pretty printed JSON document.
+    Json,
+    /// One JSON row per location and day, a line each.
+    Ndjson,
+    /// One CSV row per location and day, after a header.
+    Csv,
+    /// One YAML document.
+    Yaml,
+    /// One line a day.
+    Compact,
+    /// Every detail, with the feed's metadata.
+    Verbose,
+    /// Days side by side in a table.
+    Table,
+    /// A sentence a day.
+    Prose,
+    /// The same sentences as SSML, for text to speech.
+    Ssml,
+    /// A GitHub-flavoured Markdown document.
+    Markdown,
+    /// A self-contained HTML page.
+    Html,
+    /// JSON for a waybar custom module.
+    Waybar,
+    /// One i3bar protocol block, for i3blocks.
+    I3bar,
+    /// One line for tmux's status bar.
+    Tmux,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "compact" => Ok(OutputFormat::Compact),
+            "verbose" => Ok(OutputFormat::Verbose),
+            "table" => Ok(OutputFormat::Table),
+            "prose" => Ok(OutputFormat::Prose),
+            "ssml" => Ok(OutputFormat::Ssml),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "html" => Ok(OutputFormat::Html),
+            "waybar" => Ok(OutputFormat::Waybar),
+            "i3bar" => Ok(OutputFormat::I3bar),
+            "tmux" => Ok(OutputFormat::Tmux),
+            _ => Err(format!(
+                "\"{}\" is not an output format, expected text, compact, verbose, table, \
+                 prose, ssml, markdown, html, json, ndjson, csv, yaml, waybar, i3bar or tmux",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Ndjson => write!(f, "ndjson"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Yaml => write!(f, "yaml"),
+            OutputFormat::Compact => write!(f, "compact"),
+            OutputFormat::Verbose => write!(f, "verbose"),
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Prose => write!(f, "prose"),
+            OutputFormat::Ssml => write!(f, "ssml"),
+            OutputFormat::Markdown => write!(f, "markdown"),
+            OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Waybar => write!(f, "waybar"),
+            OutputFormat::I3bar => write!(f, "i3bar"),
+            OutputFormat::Tmux => write!(f, "tmux"),
+        }
+    }
+}
+
+/// The forecast for one location.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    location: Location,
+    issued: String,
+    fetched: String,
+    stale: bool,
+    days: Vec<DayReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Location {
+    /// The location as it was asked for, usually a GeoNames id.
+    id: String,
+    name: String,
+    link: String,
+    language: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+    /// Days after the forecast was issued.
+    index: usize,
+    date: String,
+    weekday: String,
+    condition: String,
+    temperature_max_c: Option<i32>,
+    temperature_max_f: Option<i32>,
+    temperature_min_c: Option<i32>,
+    temperature_min_f: Option<i32>,
+    wind_direction: String,
+    wind_speed_mph: Option<u32>,
+    visibility: String,
+    pressure_mb: Option<u32>,
+    humidity_percent: Option<u32>,
+    uv_risk: Option<u32>,
+    pollution: String,
+    sunrise: String,
+    sunset: String,
+}
+
+impl Report {
+    pub fn new(forecast: &LocationForecast) -> Report {
+        let channel = forecast.channel();
+        let issued = channel.get_pub_date();
+        Report {
+            location: Location {
+                id: String::from(forecast.location()),
+                name: location_name(channel.get_title()),
+                link: channel.get_link().to_string(),
+                language: String::from(channel.get_language()),
+            },
+            issued: issued.to_rfc3339_opts(SecondsFormat::Secs, false),
+            fetched: forecast
+                .feed()
+                .fetched()
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            stale: forecast.feed().is_stale(),
+            days: forecast
+                .days()
+                .map(|(index, daily)| {
+                    let date = issued.date_naive() + Duration::days(index as i64);
+                    DayReport::new(index, date, daily)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DayReport {
+    fn new(index: usize, date: NaiveDate, daily: &Daily) -> DayReport {
+        let details = daily.details();
+        DayReport {
+            index,
+            date: date.format("%Y-%m-%d").to_string(),
+            weekday: date.format("%A").to_string(),
+            condition: String::from(daily.summary().summary()),
+            temperature_max_c: details.temperature_max_celsius(),
+            temperature_max_f: details.temperature_max_fahrenheit(),
+            temperature_min_c: details.temperature_min_celsius(),
+            temperature_min_f: details.temperature_min_fahrenheit(),
+            wind_direction: String::from(details.wind_direction()),
+            wind_speed_mph: details.wind_speed_mph(),
+            visibility: String::from(details.visibility()),
+            pressure_mb: details.pressure_mb(),
+            humidity_percent: details.humidity_percent(),
+            uv_risk: details.uv_risk_index(),
+            pollution: String::from(details.pollution_level()),
+            sunrise: String::from(details.sunrise_time()),
+            sunset: String::from(details.sunset_time()),
+        }
+    }
+}
+
+/// The latest observation for one location.
+#[derive(Debug, Serialize)]
+pub struct ObservationReport {
+    location: Location,
+    issued: String,
+    fetched: String,
+    stale: bool,
+    /// When the station took it, as BBC Weather gives it, such as "Sunday -
+    /// 18:00 BST".
+    observed: String,
+    condition: String,
+    temperature_c: Option<i32>,
+    temperature_f: Option<i32>,
+    wind_direction: String,
+    wind_speed_mph: Option<u32>,
+    humidity_percent: Option<u32>,
+    pressure_mb: Option<u32>,
+    /// The pressure with its tendency, such as "1012mb, Falling".
+    pressure: String,
+    visibility: String,
+}
+
+impl ObservationReport {
+    pub fn new(observed: &LocationObservation) -> ObservationReport {
+        let channel = observed.channel();
+        let observation = observed.observation();
+        ObservationReport {
+            location: Location {
+                id: String::from(observed.location()),
+                name: location_name(channel.get_title()),
+                link: channel.get_link().to_string(),
+                language: String::from(channel.get_language()),
+            },
+            issued: channel
+                .get_pub_date()
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            fetched: observed
+                .feed()
+                .fetched()
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            stale: observed.feed().is_stale(),
+            observed: String::from(observation.observed()),
+            condition: String::from(observation.condition()),
+            temperature_c: observation.temperature_celsius(),
+            temperature_f: observation.temperature_fahrenheit(),
+            wind_direction: String::from(observation.wind_direction()),
+            wind_speed_mph: observation.wind_speed_mph(),
+            humidity_percent: observation.humidity_percent(),
+            pressure_mb: observation.pressure_mb(),
+            pressure: String::from(observation.pressure()),
+            visibility: String::from(observation.visibility()),
+        }
+    }
+}
+
+/// "London, GB" from a title such as "BBC Weather - Forecast for  London, GB".
+pub(crate) fn location_name(title: &str) -> String {
+    match title.split_once(" for ") {
+        Some((_, name)) => String::from(name.trim()),
+        None => String::from(title.trim()),
+    }
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    schema_version: u32,
+    forecasts: &'a [Report],
+}
+
+#[derive(Serialize)]
+struct ObservationDocument<'a> {
+    schema_version: u32,
+    observations: &'a [ObservationReport],
+}
+
+/// A flattened `Report` for one day, for NDJSON and CSV.
+#[derive(Default, Serialize)]
+struct Row<'a> {
+    schema_version: u32,
+    location_id: &'a str,
+    location_name: &'a str,
+    location_link: &'a str,
+    location_language: &'a str,
+    issued: &'a str,
+    fetched: &'a str,
+    stale: bool,
+    index: usize,
+    date: &'a str,
+    weekday: &'a str,
+    condition: &'a str,
+    temperature_max_c: Option<i32>,
+    temperature_max_f: Option<i32>,
+    temperature_min_c: Option<i32>,
+    temperature_min_f: Option<i32>,
+    wind_direction: &'a str,
+    wind_speed_mph: Option<u32>,
+    visibility: &'a str,
+    pressure_mb: Option<u32>,
+    humidity_percent: Option<u32>,
+    uv_risk: Option<u32>,
+    pollution: &'a str,
+    sunrise: &'a str,
+    sunset: &'a str,
+}
+
+fn rows(reports: &[Report]) -> impl Iterator<Item = Row<'_>> {
+    reports.iter().flat_map(|report| {
+        report.days.iter().map(move |day| Row {
+            schema_version: SCHEMA_VERSION,
+            location_id: &report.location.id,
+            location_name: &report.location.name,
+            location_link: &report.location.link,
+            location_language: &report.location.language,
+            issued: &report.issued,
+            fetched: &report.fetched,
+            stale: report.stale,
+            index: day.index,
+            date: &day.date,
+            weekday: &day.weekday,
+            condition: &day.condition,
+            temperature_max_c: day.temperature_max_c,
+            temperature_max_f: day.temperature_max_f,
+            temperature_min_c: day.temperature_min_c,
+            temperature_min_f: day.temperature_min_f,
+            wind_direction: &day.wind_direction,
+            wind_speed_mph: day.wind_speed_mph,
+            visibility: &day.visibility,
+            pressure_mb: day.pressure_mb,
+            humidity_percent: day.humidity_percent,
+            uv_risk: day.uv_risk,
+            pollution: &day.pollution,
+            sunrise: &day.sunrise,
+            sunset: &day.sunset,
+        })
+    })
+}
+
+/// A flattened `ObservationReport`, for NDJSON and CSV.
+#[derive(Default, Serialize)]
+struct ObservationRow<'a> {
+    schema_version: u32,
+    location_id: &'a str,
+    location_name: &'a str,
+    location_link: &'a str,
+    location_language: &'a str,
+    issued: &'a str,
+    fetched: &'a str,
+    stale: bool,
+    observed: &'a str,
+    condition: &'a str,
+    temperature_c: Option<i32>,
+    temperature_f: Option<i32>,
+    wind_direction: &'a str,
+    wind_speed_mph: Option<u32>,
+    humidity_percent: Option<u32>,
+    pressure_mb: Option<u32>,
+    pressure: &'a str,
+    visibility: &'a str,
+}
+
+fn observation_rows(reports: &[ObservationReport]) -> impl Iterator<Item = ObservationRow<'_>> {
+    reports.iter().map(|report| ObservationRow {
+        schema_version: SCHEMA_VERSION,
+        location_id: &report.location.id,
+        location_name: &report.location.name,
+        location_link: &report.location.link,
+        location_language: &report.location.language,
+        issued: &report.issued,
+        fetched: &report.fetched,
+        stale: report.stale,
+        observed: &report.observed,
+        condition: &report.condition,
+        temperature_c: report.temperature_c,
+        temperature_f: report.temperature_f,
+        wind_direction: &report.wind_direction,
+        wind_speed_mph: report.wind_speed_mph,
+        humidity_percent: report.humidity_percent,
+        pressure_mb: report.pressure_mb,
+        pressure: &report.pressure,
+        visibility: &report.visibility,
+    })
+}
+
+/// Wraps a serialisation error, as opposed to a failure to write.
+fn invalid<E>(e: E) -> io::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+// Each format is serialised in full before any of it is written, so a
+// failed write, such as to a closed pipe, is reported as it happened.
+
+pub fn write_json<W: Write>(reports: &[Report], out: W) -> io::Result<()> {
+    json_document(
+        &Document {
+            schema_version: SCHEMA_VERSION,
+            forecasts: reports,
+        },
+        out,
+    )
+}
+
+pub fn write_ndjson<W: Write>(reports: &[Report], out: W) -> io::Result<()> {
+    json_rows(rows(reports), out)
+}
+
+pub fn write_csv<W: Write>(reports: &[Report], out: W) -> io::Result<()> {
+    csv_rows(rows(reports), out)
+}
+
+pub fn write_yaml<W: Write>(reports: &[Report], out: W) -> io::Result<()> {
+    yaml_document(
+        &Document {
+            schema_version: SCHEMA_VERSION,
+            forecasts: reports,
+        },
+        out,
+    )
+}
+
+/// Writes observations in `format`, which must be JSON, NDJSON, CSV or
+/// YAML.
+pub fn write_observations<W: Write>(
+    format: OutputFormat,
+    reports: &[ObservationReport],
+    out: W,
+) -> io::Result<()> {
+    let document = ObservationDocument {
+        schema_version: SCHEMA_VERSION,
+        observations: reports,
+    };
+    match format {
+        OutputFormat::Json => json_document(&document, out),
+        OutputFormat::Ndjson => json_rows(observation_rows(reports), out),
+        OutputFormat::Csv => csv_rows(observation_rows(reports), out),
+        OutputFormat::Yaml => yaml_document(&document, out),
+        format => unreachable!("{} is not a structured format", format),
+    }
+}
+
+fn json_document<T: Serialize, W: Write>(document: &T, mut out: W) -> io::Result<()> {
+    let mut buffer = serde_json::to_vec_pretty(document).map_err(invalid)?;
+    buffer.push(b'\n');
+    out.write_all(&buffer)
+}
+
+fn json_rows<T: Serialize, W: Write>(rows: impl Iterator<Item = T>, mut out: W) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut buffer, &row).map_err(invalid)?;
+        buffer.push(b'\n');
+    }
+    out.write_all(&buffer)
+}
+
+fn csv_rows<T, W>(rows: impl Iterator<Item = T>, mut out: W) -> io::Result<()>
+where
+    T: Serialize + Default,
+    W: Write,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut empty = true;
+    for row in rows {
+        writer.serialize(row).map_err(invalid)?;
+        empty = false;
+    }
+    // The csv crate only writes the header before the first row, so with
+    // no rows write a blank one and keep just the header.
+    if empty {
+        writer.serialize(T::default()).map_err(invalid)?;
+    }
+    let mut buffer = writer.into_inner().map_err(|e| invalid(e.into_error()))?;
+    if empty {
+        let header = buffer
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(0, |end| end + 1);
+        buffer.truncate(header);
+    }
+    out.write_all(&buffer)
+}
+
+fn yaml_document<T: Serialize, W: Write>(document: &T, mut out: W) -> io::Result<()> {
+    let buffer = serde_yaml::to_string(document).map_err(invalid)?;
+    out.write_all(buffer.as_bytes())
+}
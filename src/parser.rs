@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::error::{Error, ParseError};
 
 use chrono::{DateTime, FixedOffset};
 use quick_xml::events::Event;
@@ -6,6 +6,7 @@ use quick_xml::Reader;
 use url::Url;
 
 const RSS_EL: &[u8] = b"rss";
+const CHANNEL_EL: &[u8] = b"channel";
 const TITLE_EL: &[u8] = b"title";
 const LINK_EL: &[u8] = b"link";
 const DESCRIPTION_EL: &[u8] = b"description";
@@ -17,7 +18,7 @@ const IMAGE_EL: &[u8] = b"image";
 const GUID_EL: &[u8] = b"guid";
 const URL_EL: &[u8] = b"url";
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Channel {
     title: String,
     link: Url,
@@ -30,19 +31,40 @@ pub struct Channel {
 }
 
 impl Channel {
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+    pub fn get_link(&self) -> &Url {
+        &self.link
+    }
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+    pub fn get_copyright(&self) -> &str {
+        &self.copyright
+    }
+    pub fn get_image(&self) -> &Image {
+        &self.image
+    }
     pub fn get_items(&self) -> &Vec<Item> {
         &self.items
     }
+    pub fn get_pub_date(&self) -> &DateTime<FixedOffset> {
+        &self.pub_date
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Image {
     title: String,
     url: Url,
     link: Url,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Item {
     title: String,
     link: Url,
@@ -51,16 +73,37 @@ pub struct Item {
     guid: Url,
 }
 
+impl Image {
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+    pub fn get_url(&self) -> &Url {
+        &self.url
+    }
+    pub fn get_link(&self) -> &Url {
+        &self.link
+    }
+}
+
 impl Item {
     pub fn get_title(&self) -> &str {
         &self.title
     }
+    pub fn get_link(&self) -> &Url {
+        &self.link
+    }
     pub fn get_description(&self) -> &str {
         &self.description
     }
+    pub fn get_pub_date(&self) -> &DateTime<FixedOffset> {
+        &self.pub_date
+    }
+    pub fn get_guid(&self) -> &Url {
+        &self.guid
+    }
 }
 
-pub struct ChannelBuilder {
+pub(crate) struct ChannelBuilder {
     title: Option<String>,
     link: Option<Url>,
     description: Option<String>,
@@ -101,20 +144,21 @@ impl ChannelBuilder {
         items.push(item);
         self.items = Some(items);
     }
-    pub fn build(&mut self) -> Channel {
-        Channel {
-            title: self.title.take().unwrap(),
-            link: self.link.take().unwrap(),
-            description: self.description.take().unwrap(),
-            language: self.language.take().unwrap(),
-            copyright: self.copyright.take().unwrap(),
-            pub_date: self.pub_date.take().unwrap(),
-            image: self.image.take().unwrap(),
-            items: self.items.take().unwrap(),
-        }
+    pub fn build(&mut self, end: &End) -> Result<Channel, Error> {
+        Ok(Channel {
+            title: required(&mut self.title, "title", end)?,
+            link: required(&mut self.link, "link", end)?,
+            description: required(&mut self.description, "description", end)?,
+            language: required(&mut self.language, "language", end)?,
+            copyright: required(&mut self.copyright, "copyright", end)?,
+            pub_date: required(&mut self.pub_date, "pubDate", end)?,
+            image: required(&mut self.image, "image", end)?,
+            items: self.items.take().unwrap_or_default(),
+        })
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for ChannelBuilder {
     fn default() -> Self {
         ChannelBuilder {
@@ -130,7 +174,7 @@ impl Default for ChannelBuilder {
     }
 }
 
-pub struct ImageBuilder {
+pub(crate) struct ImageBuilder {
     title: Option<String>,
     url: Option<Url>,
     link: Option<Url>,
@@ -146,15 +190,16 @@ impl ImageBuilder {
     pub fn set_link(&mut self, link: Url) {
         self.link.replace(link);
     }
-    pub fn build(&mut self) -> Image {
-        Image {
-            title: self.title.take().unwrap(),
-            url: self.url.take().unwrap(),
-            link: self.link.take().unwrap(),
-        }
+    pub fn build(&mut self, end: &End) -> Result<Image, Error> {
+        Ok(Image {
+            title: required(&mut self.title, "title", end)?,
+            url: required(&mut self.url, "url", end)?,
+            link: required(&mut self.link, "link", end)?,
+        })
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for ImageBuilder {
     fn default() -> Self {
         ImageBuilder {
@@ -165,7 +210,7 @@ impl Default for ImageBuilder {
     }
 }
 
-pub struct ItemBuilder {
+pub(crate) struct ItemBuilder {
     title: Option<String>,
     link: Option<Url>,
     description: Option<String>,
@@ -189,17 +234,18 @@ impl ItemBuilder {
     pub fn set_guid(&mut self, guid: Url) {
         self.guid.replace(guid);
     }
-    pub fn build(&mut self) -> Item {
-        Item {
-            title: self.title.take().unwrap(),
-            link: self.link.take().unwrap(),
-            description: self.description.take().unwrap(),
-            pub_date: self.pub_date.take().unwrap(),
-            guid: self.guid.take().unwrap(),
-        }
+    pub fn build(&mut self, end: &End) -> Result<Item, Error> {
+        Ok(Item {
+            title: required(&mut self.title, "title", end)?,
+            link: required(&mut self.link, "link", end)?,
+            description: required(&mut self.description, "description", end)?,
+            pub_date: required(&mut self.pub_date, "pubDate", end)?,
+            guid: required(&mut self.guid, "guid", end)?,
+        })
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for ItemBuilder {
     fn default() -> Self {
         ItemBuilder {
@@ -212,13 +258,45 @@ impl Default for ItemBuilder {
     }
 }
 
-pub enum Destination {
+/// Where in the document an element being built ended.
+pub(crate) struct End<'a> {
+    body: &'a str,
+    position: usize,
+    path: &'a [String],
+}
+
+impl End<'_> {
+    fn missing(&self, element: &'static str) -> Error {
+        ParseError::missing(self.body, self.position, self.path, element).into()
+    }
+}
+
+/// Takes a field out of a builder, failing if the feed never set it.
+fn required<T>(field: &mut Option<T>, element: &'static str, end: &End) -> Result<T, Error> {
+    field.take().ok_or_else(|| end.missing(element))
+}
+
+pub(crate) enum Destination {
     Channel,
     Image,
     Item,
 }
 
-pub struct Proxy {
+impl Destination {
+    fn invalid(&self, element: &'static str) -> Error {
+        let destination = match self {
+            Destination::Channel => "channel",
+            Destination::Image => "image",
+            Destination::Item => "item",
+        };
+        Error::InvalidDestination {
+            element,
+            destination,
+        }
+    }
+}
+
+pub(crate) struct Proxy {
     channel_builder: ChannelBuilder,
     image_builder: ImageBuilder,
     item_builder: ItemBuilder,
@@ -240,7 +318,7 @@ impl Proxy {
         }
     }
     pub fn set_link(&mut self, destination: &Destination, link: &str) -> Result<(), Error> {
-        let link = TryFrom::<Url>::get(&link)?;
+        let link = TryFrom::<Url>::get(&link, "link")?;
         match destination {
             Destination::Channel => self.channel_builder.set_link(link),
             Destination::Image => self.image_builder.set_link(link),
@@ -255,7 +333,7 @@ impl Proxy {
     ) -> Result<(), Error> {
         match destination {
             Destination::Channel => self.channel_builder.set_description(description),
-            Destination::Image => return Err(Error::InvalidDestination),
+            Destination::Image => return Err(destination.invalid("description")),
             Destination::Item => self.item_builder.set_description(description),
         }
         Ok(())
@@ -263,7 +341,7 @@ impl Proxy {
     pub fn set_language(&mut self, destination: &Destination, language: &str) -> Result<(), Error> {
         match destination {
             Destination::Channel => self.channel_builder.set_language(language),
-            _ => return Err(Error::InvalidDestination),
+            _ => return Err(destination.invalid("language")),
         }
         Ok(())
     }
@@ -274,66 +352,76 @@ impl Proxy {
     ) -> Result<(), Error> {
         match destination {
             Destination::Channel => self.channel_builder.set_copyright(copyright),
-            _ => return Err(Error::InvalidDestination),
+            _ => return Err(destination.invalid("copyright")),
         }
         Ok(())
     }
     pub fn set_pub_date(&mut self, destination: &Destination, pub_date: &str) -> Result<(), Error> {
-        let pub_date = TryFrom::<DateTime<FixedOffset>>::get(&pub_date)?;
+        let pub_date = TryFrom::<DateTime<FixedOffset>>::get(&pub_date, "pubDate")?;
         match destination {
             Destination::Channel => self.channel_builder.set_pub_date(pub_date),
-            Destination::Image => return Err(Error::InvalidDestination),
+            Destination::Image => return Err(destination.invalid("pubDate")),
             Destination::Item => self.item_builder.set_pub_date(pub_date),
         }
         Ok(())
     }
-    pub fn set_image(&mut self) {
-        let image = self.image_builder.build();
+    pub fn set_image(&mut self, end: &End) -> Result<(), Error> {
+        let image = self.image_builder.build(end)?;
         self.channel_builder.set_image(image);
         self.image_builder = ImageBuilder::default();
+        Ok(())
     }
-    pub fn set_item(&mut self) {
-        let item = self.item_builder.build();
+    pub fn set_item(&mut self, end: &End) -> Result<(), Error> {
+        let item = self.item_builder.build(end)?;
         self.channel_builder.set_items(item);
         self.item_builder = ItemBuilder::default();
+        Ok(())
     }
     pub fn set_url(&mut self, destination: &Destination, url: &str) -> Result<(), Error> {
-        let url = TryFrom::<Url>::get(&url)?;
+        let url = TryFrom::<Url>::get(&url, "url")?;
         match destination {
-            Destination::Channel => return Err(Error::InvalidDestination),
+            Destination::Channel => return Err(destination.invalid("url")),
             Destination::Image => self.image_builder.set_url(url),
-            Destination::Item => return Err(Error::InvalidDestination),
+            Destination::Item => return Err(destination.invalid("url")),
         }
         Ok(())
     }
     pub fn set_guid(&mut self, destination: &Destination, guid: &str) -> Result<(), Error> {
-        let guid = TryFrom::<Url>::get(&guid)?;
+        let guid = TryFrom::<Url>::get(&guid, "guid")?;
         match destination {
-            Destination::Channel => return Err(Error::InvalidDestination),
-            Destination::Image => return Err(Error::InvalidDestination),
+            Destination::Channel => return Err(destination.invalid("guid")),
+            Destination::Image => return Err(destination.invalid("guid")),
             Destination::Item => self.item_builder.set_guid(guid),
         }
         Ok(())
     }
-    pub fn get(&mut self) -> Channel {
-        self.channel_builder.build()
+    pub fn get(&mut self, end: &End) -> Result<Channel, Error> {
+        self.channel_builder.build(end)
     }
 }
 
 trait TryFrom<T> {
-    fn get(&self) -> Result<T, Error>;
+    fn get(&self, field: &'static str) -> Result<T, Error>;
 }
 
 impl TryFrom<DateTime<FixedOffset>> for &str {
-    fn get(&self) -> Result<DateTime<FixedOffset>, Error> {
-        let result = DateTime::parse_from_rfc2822(self)?;
+    fn get(&self, field: &'static str) -> Result<DateTime<FixedOffset>, Error> {
+        let result = DateTime::parse_from_rfc2822(self).map_err(|source| Error::Date {
+            field,
+            value: String::from(*self),
+            source,
+        })?;
         Ok(result)
     }
 }
 
 impl TryFrom<Url> for &str {
-    fn get(&self) -> Result<Url, Error> {
-        let url = Url::parse(self)?;
+    fn get(&self, field: &'static str) -> Result<Url, Error> {
+        let url = Url::parse(self).map_err(|source| Error::Url {
+            field,
+            value: String::from(*self),
+            source,
+        })?;
         Ok(url)
     }
 }
@@ -361,64 +449,83 @@ pub fn parse_document(body: &str) -> Result<Channel, Error> {
     let mut text_buffer = String::new();
     let mut state = State::Initial;
     let mut target = Destination::Channel;
+    let mut path: Vec<String> = Vec::new();
 
     let mut proxy = Proxy::new();
+    let mut channel = None;
 
     while state != State::Complete {
         match reader.read_event(&mut buffer) {
-            Ok(Event::Start(ref e)) => match e.name() {
-                RSS_EL => state = State::Rss,
-                TITLE_EL => state = State::Title,
-                LINK_EL => state = State::Link,
-                DESCRIPTION_EL => state = State::Description,
-                LANGUAGE_EL => state = State::Language,
-                COPYRIGHT_EL => state = State::Copyright,
-                PUBDATE_EL => state = State::PubDate,
-                IMAGE_EL => {
-                    state = State::Image;
-                    target = Destination::Image;
-                }
-                ITEM_EL => {
-                    state = State::Item;
-                    target = Destination::Item;
+            Ok(Event::Start(ref e)) => {
+                path.push(String::from_utf8_lossy(e.name()).into_owned());
+                match e.name() {
+                    RSS_EL => state = State::Rss,
+                    TITLE_EL => state = State::Title,
+                    LINK_EL => state = State::Link,
+                    DESCRIPTION_EL => state = State::Description,
+                    LANGUAGE_EL => state = State::Language,
+                    COPYRIGHT_EL => state = State::Copyright,
+                    PUBDATE_EL => state = State::PubDate,
+                    IMAGE_EL => {
+                        state = State::Image;
+                        target = Destination::Image;
+                    }
+                    ITEM_EL => {
+                        state = State::Item;
+                        target = Destination::Item;
+                    }
+                    GUID_EL => state = State::Guid,
+                    URL_EL => state = State::Url,
+                    _ => (),
                 }
-                GUID_EL => state = State::Guid,
-                URL_EL => state = State::Url,
-                _ => (),
-            },
+            }
             Ok(Event::Text(e)) => {
                 text_buffer = match e.unescape_and_decode(&reader) {
                     Ok(text_buffer) => text_buffer,
-                    Err(_) => return Err(Error::ParseLibrary),
+                    Err(e) => {
+                        return Err(ParseError::new(body, reader.buffer_position(), &path, e).into())
+                    }
                 };
             }
-            Ok(Event::End(ref e)) => match e.name() {
-                RSS_EL => state = State::Complete,
-                TITLE_EL => proxy.set_title(&target, &text_buffer),
-                LINK_EL => proxy.set_link(&target, &text_buffer)?,
-                DESCRIPTION_EL => proxy.set_description(&target, &text_buffer)?,
-                LANGUAGE_EL => proxy.set_language(&target, &text_buffer)?,
-                COPYRIGHT_EL => proxy.set_copyright(&target, &text_buffer)?,
-                PUBDATE_EL => proxy.set_pub_date(&target, &text_buffer)?,
-                IMAGE_EL => {
-                    proxy.set_image();
-                    target = Destination::Channel;
-                }
-                ITEM_EL => {
-                    proxy.set_item();
-                    target = Destination::Channel;
+            Ok(Event::End(ref e)) => {
+                let end = End {
+                    body,
+                    position: reader.buffer_position(),
+                    path: &path,
+                };
+                match e.name() {
+                    RSS_EL => state = State::Complete,
+                    CHANNEL_EL => channel = Some(proxy.get(&end)?),
+                    TITLE_EL => proxy.set_title(&target, &text_buffer),
+                    LINK_EL => proxy.set_link(&target, &text_buffer)?,
+                    DESCRIPTION_EL => proxy.set_description(&target, &text_buffer)?,
+                    LANGUAGE_EL => proxy.set_language(&target, &text_buffer)?,
+                    COPYRIGHT_EL => proxy.set_copyright(&target, &text_buffer)?,
+                    PUBDATE_EL => proxy.set_pub_date(&target, &text_buffer)?,
+                    IMAGE_EL => {
+                        proxy.set_image(&end)?;
+                        target = Destination::Channel;
+                    }
+                    ITEM_EL => {
+                        proxy.set_item(&end)?;
+                        target = Destination::Channel;
+                    }
+                    GUID_EL => proxy.set_guid(&target, &text_buffer)?,
+                    URL_EL => proxy.set_url(&target, &text_buffer)?,
+                    _ => (),
                 }
-                GUID_EL => proxy.set_guid(&target, &text_buffer)?,
-                URL_EL => proxy.set_url(&target, &text_buffer)?,
-                _ => (),
-            },
-            Ok(Event::Eof) => (),
+                path.pop();
+            }
+            Ok(Event::Eof) => {
+                let e = quick_xml::Error::UnexpectedEof(String::from("rss"));
+                return Err(ParseError::new(body, reader.buffer_position(), &path, e).into());
+            }
             Err(e) => {
-                eprintln!("Error at position {}: {:?}", reader.buffer_position(), e);
-                return Err(Error::ParseLibrary);
+                return Err(ParseError::new(body, reader.buffer_position(), &path, e).into());
             }
             _ => (),
         }
     }
-    Ok(proxy.get())
+    let position = reader.buffer_position();
+    channel.ok_or_else(|| ParseError::missing(body, position, &path, "channel").into())
 }
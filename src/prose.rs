@@ -0,0 +1,371 @@
+//! Forecasts as sentences, for screen readers and text to speech:
+//!
+//! > Tomorrow will be light rain, highs of 14 degrees, lows of 9 degrees, a
+//! > westerly gale and low UV.
+//!
+//! Every phrase comes from `Phrases`, which can be translated in the
+//! `[prose]` table of `config.toml`. Phrases fill in placeholders such as
+//! `{day}` and `{temperature}`, listed with each field of `Phrases`.
+
+use std::io::{self, Write};
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::dayoption::Day;
+use crate::model::Daily;
+use crate::render::{RenderOptions, Renderer};
+use crate::template;
+use crate::units::Units;
+use crate::LocationForecast;
+
+/// UV risk indices from which the risk is moderate, high, very high and
+/// extreme.
+const UV_RISK_STARTS: [u32; 4] = [3, 6, 8, 11];
+
+/// The phrases sentences are built from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Phrases {
+    /// A day with details: `{day}`, `{condition}` and `{details}`.
+    pub sentence: String,
+    /// A day without any details: `{day}` and `{condition}`.
+    pub brief_sentence: String,
+    /// Introduces a location when there are several: `{location}`.
+    pub location: String,
+    /// Warns of a stale feed: `{fetched}`.
+    pub stale: String,
+    /// When a stale feed was fetched, in UTC: `{time}`, `{date}` and
+    /// `{month}`.
+    pub fetched_format: String,
+    /// January to December, for `{month}`.
+    pub months: [String; 12],
+    /// Joins the last two details.
+    pub and: String,
+    /// Today, tomorrow and the day after.
+    pub days: [String; 3],
+    /// Monday to Sunday, for a day chosen by its name or date.
+    pub weekdays: [String; 7],
+    /// `{temperature}`
+    pub high: String,
+    /// `{temperature}`
+    pub low: String,
+    /// A temperature: `{value}`.
+    pub degrees: String,
+    /// The wind at Beaufort force 0 to 12: `{direction}`, such as
+    /// "south-westerly".
+    pub wind: [String; 13],
+    /// Low, moderate, high, very high and extreme UV risk.
+    pub uv_risk: [String; 5],
+}
+
+impl Default for Phrases {
+    fn default() -> Self {
+        Phrases {
+            sentence: String::from("{day} will be {condition}, {details}."),
+            brief_sentence: String::from("{day} will be {condition}."),
+            location: String::from("The forecast for {location}."),
+            stale: String::from(
+                "BBC Weather could not be reached, so this forecast from {fetched} may be out of date.",
+            ),
+            fetched_format: String::from("{time} on {date} {month}"),
+            months: [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ]
+            .map(String::from),
+            and: String::from("and"),
+            days: ["today", "tomorrow", "the day after tomorrow"].map(String::from),
+            weekdays: [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ]
+            .map(String::from),
+            high: String::from("highs of {temperature}"),
+            low: String::from("lows of {temperature}"),
+            degrees: String::from("{value} degrees"),
+            wind: [
+                "calm winds",
+                "light {direction} air",
+                "a light {direction} breeze",
+                "a gentle {direction} breeze",
+                "a moderate {direction} breeze",
+                "a fresh {direction} breeze",
+                "a strong {direction} breeze",
+                "a {direction} near gale",
+                "a {direction} gale",
+                "a severe {direction} gale",
+                "a {direction} storm",
+                "a violent {direction} storm",
+                "{direction} hurricane force winds",
+            ]
+            .map(String::from),
+            uv_risk: [
+                "low UV",
+                "moderate UV",
+                "high UV",
+                "very high UV",
+                "extreme UV",
+            ]
+            .map(String::from),
+        }
+    }
+}
+
+impl Phrases {
+    /// The chosen day as it was asked for, or the day `index` days after
+    /// the forecast was issued unless that is later than the day after
+    /// tomorrow.
+    pub fn day_name(&self, forecast: &LocationForecast, index: usize) -> Option<&str> {
+        let name = match forecast.day() {
+            Some(Day::Today) => &self.days[0],
+            Some(Day::Tomorrow) => &self.days[1],
+            Some(Day::Overmorrow) => &self.days[2],
+            Some(Day::Weekday(weekday)) => &self.weekdays[weekday.num_days_from_monday() as usize],
+            Some(Day::Date(date)) => &self.weekdays[date.weekday().num_days_from_monday() as usize],
+            None => self.days.get(index)?,
+        };
+        Some(name)
+    }
+
+    /// A sentence describing `daily`, called `day`, in `units`.
+    pub fn describe(&self, day: &str, daily: &Daily, units: Units) -> String {
+        let details = daily.details();
+        let temperatures = match units {
+            Units::Imperial => [
+                details.temperature_max_fahrenheit(),
+                details.temperature_min_fahrenheit(),
+            ],
+            Units::Metric | Units::Both => [
+                details.temperature_max_celsius(),
+                details.temperature_min_celsius(),
+            ],
+        };
+        let mut clauses = Vec::new();
+        for (phrase, temperature) in [&self.high, &self.low].iter().zip(&temperatures) {
+            if let Some(value) = temperature {
+                let temperature = fill(&self.degrees, &[("value", &value.to_string())]);
+                clauses.push(fill(phrase, &[("temperature", &temperature)]));
+            }
+        }
+        if let Some(mph) = details.wind_speed_mph() {
+            let direction = adjective(details.wind_direction());
+            let phrase = &self.wind[template::beaufort(mph).min(12)];
+            clauses.push(fill(phrase, &[("direction", &direction)]));
+        }
+        if let Some(index) = details.uv_risk_index() {
+            let risk = UV_RISK_STARTS
+                .iter()
+                .take_while(|start| index >= **start)
+                .count();
+            clauses.push(self.uv_risk[risk].clone());
+        }
+        let condition = daily.summary().summary().to_lowercase();
+        let sentence = if clauses.is_empty() {
+            fill(
+                &self.brief_sentence,
+                &[("day", day), ("condition", &condition)],
+            )
+        } else {
+            fill(
+                &self.sentence,
+                &[
+                    ("day", day),
+                    ("condition", &condition),
+                    ("details", &self.list(clauses)),
+                ],
+            )
+        };
+        capitalise(&sentence)
+    }
+
+    /// Every sentence for one location, including any location and stale
+    /// warning sentences.
+    fn sentences(&self, forecast: &LocationForecast, several: bool, units: Units) -> Vec<String> {
+        let mut sentences = Vec::new();
+        if several {
+            let location = crate::output::location_name(forecast.channel().get_title());
+            sentences.push(fill(&self.location, &[("location", &location)]));
+        }
+        if forecast.feed().is_stale() {
+            let fetched = forecast.feed().fetched();
+            let fetched = fill(
+                &self.fetched_format,
+                &[
+                    ("time", &fetched.format("%H:%M").to_string()),
+                    ("date", &fetched.day().to_string()),
+                    ("month", &self.months[fetched.month0() as usize]),
+                ],
+            );
+            sentences.push(capitalise(&fill(&self.stale, &[("fetched", &fetched)])));
+        }
+        sentences.extend(forecast.days().filter_map(|(index, daily)| {
+            Some(self.describe(self.day_name(forecast, index)?, daily, units))
+        }));
+        sentences
+    }
+
+    /// "a, b and c"
+    fn list(&self, mut clauses: Vec<String>) -> String {
+        match clauses.pop() {
+            Some(last) if !clauses.is_empty() => {
+                format!("{} {} {}", clauses.join(", "), self.and, last)
+            }
+            Some(last) => last,
+            None => String::new(),
+        }
+    }
+}
+
+/// Sentences for each day, a paragraph for each location.
+pub struct Prose {
+    phrases: Phrases,
+}
+
+impl Prose {
+    pub fn new(phrases: Phrases) -> Prose {
+        Prose { phrases }
+    }
+}
+
+impl Default for Prose {
+    fn default() -> Self {
+        Prose::new(Phrases::default())
+    }
+}
+
+impl Renderer for Prose {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        for (number, forecast) in forecasts.iter().enumerate() {
+            if number > 0 {
+                writeln!(out)?;
+            }
+            let sentences = self
+                .phrases
+                .sentences(forecast, forecasts.len() > 1, options.units());
+            writeln!(out, "{}", sentences.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The same sentences as `Prose`, as one SSML document for text to speech,
+/// in the language of the first feed.
+pub struct Ssml {
+    phrases: Phrases,
+}
+
+impl Ssml {
+    pub fn new(phrases: Phrases) -> Ssml {
+        Ssml { phrases }
+    }
+}
+
+impl Default for Ssml {
+    fn default() -> Self {
+        Ssml::new(Phrases::default())
+    }
+}
+
+impl Renderer for Ssml {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let language = forecasts
+            .first()
+            .map(|forecast| forecast.channel().get_language())
+            .unwrap_or("en-gb");
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="{}">"#,
+            escape(language)
+        )?;
+        for forecast in forecasts {
+            writeln!(out, "  <p>")?;
+            let sentences = self
+                .phrases
+                .sentences(forecast, forecasts.len() > 1, options.units());
+            for sentence in sentences {
+                writeln!(out, "    <s>{}</s>", escape(&sentence))?;
+            }
+            writeln!(out, "  </p>")?;
+        }
+        writeln!(out, "</speak>")
+    }
+}
+
+/// Replaces each `{name}` in `phrase` with its value, in one pass so that
+/// braces in the values are left as they are. Unknown names are kept.
+fn fill(phrase: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::new();
+    let mut rest = phrase;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            let (_, value) = values.iter().find(|(known, _)| *known == name)?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                filled.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// "south-westerly" for "South Westerly".
+fn adjective(direction: &str) -> String {
+    direction
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn capitalise(sentence: &str) -> String {
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
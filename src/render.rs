@@ -0,0 +1,453 @@
+//! Writes forecasts and observations out, for people or for scripts.
+//!
+//! Every output format is a `Renderer`, looked up by name in `Renderers`.
+//! Library users can register their own alongside the built in ones:
+//!
+//! ```no_run
+//! use std::io::{self, Write};
+//! use text_weather::render::{RenderOptions, Renderer, Renderers};
+//! use text_weather::LocationForecast;
+//!
+//! struct Summaries;
+//!
+//! impl Renderer for Summaries {
+//!     fn render(
+//!         &self,
+//!         out: &mut dyn Write,
+//!         forecasts: &[LocationForecast],
+//!         _: &RenderOptions,
+//!     ) -> io::Result<()> {
+//!         for forecast in forecasts {
+//!             for (_, daily) in forecast.days() {
+//!                 writeln!(out, "{}", daily.summary().summary())?;
+//!             }
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut renderers = Renderers::default();
+//! renderers.register("summaries", Summaries);
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use chrono::Duration;
+use unicode_width::UnicodeWidthStr;
+
+use crate::document::{Html, Markdown};
+use crate::error::Error;
+use crate::fetcher::Feed;
+use crate::icon::Icons;
+use crate::model::Details;
+use crate::output::{self, ObservationReport, OutputFormat, Report};
+use crate::prose::{Prose, Ssml};
+use crate::statusbar::{I3bar, Tmux, Waybar};
+use crate::style::Palette;
+use crate::table::{Borders, Table};
+use crate::units::Units;
+use crate::{LocationForecast, LocationObservation};
+
+const DAY_NAMES: [&str; 3] = ["Today", "Tomorrow", "Overmorrow"];
+
+/// Settings shared by every renderer.
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    units: Units,
+    palette: Palette,
+    width: Option<usize>,
+    borders: Borders,
+    icons: Icons,
+}
+
+impl RenderOptions {
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+    }
+    /// The units to show temperatures and wind speeds in.
+    pub fn units(&self) -> Units {
+        self.units
+    }
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+    /// How to colour text, which is not coloured by default.
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+    pub fn set_width(&mut self, width: Option<usize>) {
+        self.width = width;
+    }
+    /// How many columns wide output should fit in, if it is limited.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+    pub fn set_borders(&mut self, borders: Borders) {
+        self.borders = borders;
+    }
+    /// The characters tables are drawn with.
+    pub fn borders(&self) -> Borders {
+        self.borders
+    }
+    pub fn set_icons(&mut self, icons: Icons) {
+        self.icons = icons;
+    }
+    /// How to picture each day's weather in text and tables, if at all.
+    pub fn icons(&self) -> Icons {
+        self.icons
+    }
+}
+
+/// Presents forecasts in one output format.
+pub trait Renderer {
+    /// Writes `forecasts`, in the order their locations were asked for, to
+    /// `out`.
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()>;
+}
+
+/// Renderers by name, which start with one for each `OutputFormat`.
+pub struct Renderers {
+    renderers: BTreeMap<String, Box<dyn Renderer>>,
+}
+
+impl Default for Renderers {
+    fn default() -> Self {
+        let mut renderers = Renderers {
+            renderers: BTreeMap::new(),
+        };
+        renderers.register(&OutputFormat::Text.to_string(), Plain);
+        renderers.register(&OutputFormat::Compact.to_string(), Compact);
+        renderers.register(&OutputFormat::Verbose.to_string(), Verbose);
+        renderers.register(&OutputFormat::Table.to_string(), Table);
+        renderers.register(&OutputFormat::Prose.to_string(), Prose::default());
+        renderers.register(&OutputFormat::Ssml.to_string(), Ssml::default());
+        renderers.register(&OutputFormat::Markdown.to_string(), Markdown);
+        renderers.register(&OutputFormat::Html.to_string(), Html);
+        renderers.register(&OutputFormat::Waybar.to_string(), Waybar);
+        renderers.register(&OutputFormat::I3bar.to_string(), I3bar);
+        renderers.register(&OutputFormat::Tmux.to_string(), Tmux);
+        for format in [
+            OutputFormat::Json,
+            OutputFormat::Ndjson,
+            OutputFormat::Csv,
+            OutputFormat::Yaml,
+        ] {
+            renderers.register(&format.to_string(), Structured(format));
+        }
+        renderers
+    }
+}
+
+impl Renderers {
+    /// Adds `renderer` as `name`, replacing any renderer already called that.
+    pub fn register<R: Renderer + 'static>(&mut self, name: &str, renderer: R) {
+        self.renderers
+            .insert(String::from(name), Box::new(renderer));
+    }
+    pub fn get(&self, name: &str) -> Option<&dyn Renderer> {
+        self.renderers.get(name).map(Box::as_ref)
+    }
+    /// The names of every renderer, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.renderers.keys().map(String::as_str)
+    }
+    /// Writes `forecasts` to `out` with the renderer called `name`.
+    pub fn render<W: Write>(
+        &self,
+        name: &str,
+        mut out: W,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> Result<(), Error> {
+        let renderer = self.get(name).ok_or_else(|| Error::UnknownRenderer {
+            name: String::from(name),
+            available: self.names().map(String::from).collect(),
+        })?;
+        renderer
+            .render(&mut out, forecasts, options)
+            .and_then(|_| out.flush())
+            .map_err(|source| Error::Output {
+                format: String::from(name),
+                source: Box::new(source),
+            })
+    }
+}
+
+/// Labelled lines for each day, as text_weather has always printed them.
+pub struct Plain;
+
+impl Renderer for Plain {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let palette = options.palette;
+        for forecast in forecasts {
+            if forecasts.len() > 1 {
+                write_field(out, palette, "Location", forecast.location())?;
+            }
+            write_stale(out, palette, forecast.feed())?;
+            for (index, daily) in forecast.days() {
+                let name = match day_name(forecast, index) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                writeln!(out, "{}", palette.heading(&name))?;
+                let icon = options.icons.icon(daily.summary().kind());
+                if icon.is_empty() {
+                    write_field(out, palette, "Summary", daily.summary().summary())?;
+                    write_details(out, daily.details(), options)?;
+                } else {
+                    let mut lines = Vec::new();
+                    write_field(&mut lines, palette, "Summary", daily.summary().summary())?;
+                    write_details(&mut lines, daily.details(), options)?;
+                    write_beside(out, icon, &String::from_utf8_lossy(&lines))?;
+                }
+                writeln!(out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the lines of `text` to the right of `icon`.
+fn write_beside(out: &mut dyn Write, icon: &[&str], text: &str) -> io::Result<()> {
+    let width = icon.iter().map(|line| line.width()).max().unwrap_or(0);
+    let mut lines = text.lines();
+    for row in 0..icon.len().max(text.lines().count()) {
+        let picture = icon.get(row).copied().unwrap_or("");
+        let padding = " ".repeat(width - picture.width());
+        match lines.next() {
+            Some(line) => writeln!(out, "{}{}  {}", picture, padding, line)?,
+            None => writeln!(out, "{}", picture.trim_end())?,
+        }
+    }
+    Ok(())
+}
+
+/// A line for each day with the summary, temperatures and wind.
+pub struct Compact;
+
+impl Renderer for Compact {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let palette = options.palette;
+        for forecast in forecasts {
+            write_stale(out, palette, forecast.feed())?;
+            let location = output::location_name(forecast.channel().get_title());
+            for (index, daily) in forecast.days() {
+                let name = match day_name(forecast, index) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if forecasts.len() > 1 {
+                    write!(out, "{} - ", location)?;
+                }
+                let details = daily.details();
+                let shown = details.in_units(options.units);
+                writeln!(
+                    out,
+                    "{}: {}, {} / {}, {} {}",
+                    palette.heading(&name),
+                    daily.summary().summary(),
+                    palette.temperature(shown.temperature_max(), details.temperature_max_celsius()),
+                    palette.temperature(shown.temperature_min(), details.temperature_min_celsius()),
+                    shown.wind_direction(),
+                    palette.wind(shown.wind_speed(), details.wind_speed_mph())
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every detail of each day, dated, with where and when the forecast came
+/// from.
+pub struct Verbose;
+
+impl Renderer for Verbose {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let palette = options.palette;
+        for forecast in forecasts {
+            let channel = forecast.channel();
+            let issued = channel.get_pub_date();
+            writeln!(out, "{}", palette.heading(channel.get_title()))?;
+            write_field(out, palette, "Location", forecast.location())?;
+            write_field(out, palette, "Link", channel.get_link().as_str())?;
+            write_field(out, palette, "Language", channel.get_language())?;
+            let issued_at = issued.format("%Y-%m-%d %H:%M %:z").to_string();
+            write_field(out, palette, "Issued", &issued_at)?;
+            let fetched = forecast.feed().fetched().format("%Y-%m-%d %H:%M UTC");
+            write_field(out, palette, "Fetched", &fetched.to_string())?;
+            write_stale(out, palette, forecast.feed())?;
+            writeln!(out)?;
+            for (index, daily) in forecast.days() {
+                writeln!(out, "{}", palette.heading(&dated_day_name(forecast, index)))?;
+                write_field(out, palette, "Summary", daily.summary().summary())?;
+                write_details(out, daily.details(), options)?;
+                writeln!(out)?;
+            }
+            writeln!(out, "{}", palette.label(channel.get_copyright()))?;
+            if forecasts.len() > 1 {
+                writeln!(out)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// JSON, NDJSON, CSV or YAML, see `output`.
+struct Structured(OutputFormat);
+
+impl Renderer for Structured {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        _: &RenderOptions,
+    ) -> io::Result<()> {
+        let reports: Vec<Report> = forecasts.iter().map(Report::new).collect();
+        match self.0 {
+            OutputFormat::Json => output::write_json(&reports, out),
+            OutputFormat::Ndjson => output::write_ndjson(&reports, out),
+            OutputFormat::Csv => output::write_csv(&reports, out),
+            OutputFormat::Yaml => output::write_yaml(&reports, out),
+            format => unreachable!("{} is not a structured format", format),
+        }
+    }
+}
+
+/// Writes `observations` to `out` as text, JSON, NDJSON, CSV or YAML.
+pub fn observations<W: Write>(
+    format: OutputFormat,
+    mut out: W,
+    observations: &[LocationObservation],
+    options: &RenderOptions,
+) -> Result<(), Error> {
+    let palette = options.palette;
+    let mut write = || -> io::Result<()> {
+        if format != OutputFormat::Text {
+            let reports: Vec<ObservationReport> =
+                observations.iter().map(ObservationReport::new).collect();
+            output::write_observations(format, &reports, &mut out)?;
+            return out.flush();
+        }
+        for observed in observations {
+            if observations.len() > 1 {
+                write_field(&mut out, palette, "Location", observed.location())?;
+            }
+            write_stale(&mut out, palette, observed.feed())?;
+            let observation = observed.observation();
+            let shown = observation.in_units(options.units);
+            let temperature =
+                palette.temperature(shown.temperature(), observation.temperature_celsius());
+            let wind_speed = palette.wind(shown.wind_speed(), observation.wind_speed_mph());
+            write_field(&mut out, palette, "Observed", shown.observed())?;
+            write_field(&mut out, palette, "Condition", shown.condition())?;
+            write_field(&mut out, palette, "Temperature", &temperature)?;
+            write_field(&mut out, palette, "Wind Direction", shown.wind_direction())?;
+            write_field(&mut out, palette, "Wind Speed", &wind_speed)?;
+            write_field(&mut out, palette, "Humidity", shown.humidity())?;
+            write_field(&mut out, palette, "Pressure", shown.pressure())?;
+            write_field(&mut out, palette, "Visibility", shown.visibility())?;
+            writeln!(out)?;
+        }
+        out.flush()
+    };
+    write().map_err(|source| Error::Output {
+        format: format.to_string(),
+        source: Box::new(source),
+    })
+}
+
+/// The chosen day as it was asked for, or its name relative to when the
+/// forecast was issued.
+pub(crate) fn day_name(forecast: &LocationForecast, index: usize) -> Option<String> {
+    match forecast.day() {
+        Some(day) => Some(day.to_string()),
+        None => DAY_NAMES.get(index).map(|name| String::from(*name)),
+    }
+}
+
+/// Writes every detail, in `options.units()`, coloured by what it shows.
+fn write_details(
+    out: &mut dyn Write,
+    details: &Details,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let palette = options.palette;
+    let shown = details.in_units(options.units);
+    let max = palette.temperature(shown.temperature_max(), details.temperature_max_celsius());
+    let min = palette.temperature(shown.temperature_min(), details.temperature_min_celsius());
+    let wind_speed = palette.wind(shown.wind_speed(), details.wind_speed_mph());
+    let uv_risk = palette.uv_risk(shown.uv_risk(), details.uv_risk_index());
+    let pollution = palette.pollution(shown.pollution_level());
+    write_field(out, palette, "Maximum Temperature", &max)?;
+    write_field(out, palette, "Minimum Temperature", &min)?;
+    write_field(out, palette, "Wind Direction", shown.wind_direction())?;
+    write_field(out, palette, "Wind Speed", &wind_speed)?;
+    write_field(out, palette, "Visibility", shown.visibility())?;
+    write_field(out, palette, "Pressure", shown.pressure())?;
+    write_field(out, palette, "Humidity", shown.humidity())?;
+    write_field(out, palette, "UV Risk", &uv_risk)?;
+    write_field(out, palette, "Pollution", &pollution)?;
+    write_field(out, palette, "Sunrise", shown.sunrise_time())?;
+    write_field(out, palette, "Sunset", shown.sunset_time())
+}
+
+/// Writes "label: value" with the label dimmed.
+pub(crate) fn write_field(
+    out: &mut dyn Write,
+    palette: Palette,
+    label: &str,
+    value: &str,
+) -> io::Result<()> {
+    writeln!(out, "{} {}", palette.label(&format!("{}:", label)), value)
+}
+
+/// "Today (Sunday 2026-10-18)", or just the date after the day after
+/// tomorrow.
+pub(crate) fn dated_day_name(forecast: &LocationForecast, index: usize) -> String {
+    let date = forecast.channel().get_pub_date().date_naive() + Duration::days(index as i64);
+    match DAY_NAMES.get(index) {
+        Some(name) => format!("{} ({})", name, date.format("%A %Y-%m-%d")),
+        None => date.format("%A %Y-%m-%d").to_string(),
+    }
+}
+
+/// A warning that `feed` came from the cache because BBC Weather could not
+/// be reached, if it did.
+pub(crate) fn stale_message(feed: &Feed) -> Option<String> {
+    if feed.is_stale() {
+        Some(format!(
+            "STALE: BBC Weather could not be reached, showing the feed cached at {}",
+            feed.fetched().format("%Y-%m-%d %H:%M UTC")
+        ))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn write_stale(out: &mut dyn Write, palette: Palette, feed: &Feed) -> io::Result<()> {
+    if let Some(message) = stale_message(feed) {
+        writeln!(out, "{}", palette.warning(&message))?;
+    }
+    Ok(())
+}
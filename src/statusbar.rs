@@ -0,0 +1,214 @@
+//! Renderers for status bars, which show one short line: waybar's custom
+//! module JSON, an i3bar block for i3blocks and a line safe to use in tmux's
+//! `status-right`.
+//!
+//! Each shows the first chosen day, today unless `--day` says otherwise,
+//! for every location.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::model::{ConditionKind, Daily};
+use crate::output;
+use crate::render::{RenderOptions, Renderer};
+use crate::LocationForecast;
+
+/// Separates locations in a status line.
+const SEPARATOR: &str = " | ";
+
+/// A custom module for waybar, with `"return-type": "json"`.
+pub struct Waybar;
+
+#[derive(Serialize)]
+struct WaybarStatus {
+    text: String,
+    tooltip: String,
+    class: Vec<&'static str>,
+    alt: &'static str,
+}
+
+impl Renderer for Waybar {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let statuses: Vec<Status> = forecasts
+            .iter()
+            .filter_map(|forecast| Status::new(forecast, options))
+            .collect();
+        let kind = statuses
+            .first()
+            .map(|status| status.kind)
+            .unwrap_or(ConditionKind::Unknown);
+        let mut class = vec![kind.name()];
+        if statuses.iter().any(|status| status.stale) {
+            class.push("stale");
+        }
+        let status = WaybarStatus {
+            text: pango(&join(statuses.iter().map(|status| status.text.as_str()))),
+            tooltip: pango(&tooltip(forecasts, options)),
+            class,
+            alt: kind.name(),
+        };
+        serde_json::to_writer(&mut *out, &status)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(out)
+    }
+}
+
+/// A single i3bar protocol block on one line, covering every location, as
+/// i3blocks reads from a `format=json` command and i3status wrappers splice
+/// into the status line. Running it again refreshes the block.
+pub struct I3bar;
+
+#[derive(Serialize)]
+struct I3barBlock {
+    name: &'static str,
+    instance: String,
+    full_text: String,
+    short_text: String,
+    markup: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'static str>,
+}
+
+/// The colour stale blocks are shown in.
+const STALE_COLOUR: &str = "#888888";
+
+impl Renderer for I3bar {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let statuses: Vec<(&LocationForecast, Status)> = forecasts
+            .iter()
+            .filter_map(|forecast| Some((forecast, Status::new(forecast, options)?)))
+            .collect();
+        let block = I3barBlock {
+            name: "text_weather",
+            instance: statuses
+                .iter()
+                .map(|(forecast, _)| forecast.location())
+                .collect::<Vec<_>>()
+                .join(","),
+            full_text: join(statuses.iter().map(|(_, status)| status.text.as_str())),
+            short_text: join(
+                statuses
+                    .iter()
+                    .map(|(_, status)| status.short_text.as_str()),
+            ),
+            markup: "none",
+            color: if statuses.iter().any(|(_, status)| status.stale) {
+                Some(STALE_COLOUR)
+            } else {
+                None
+            },
+        };
+        serde_json::to_writer(&mut *out, &block)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(out)
+    }
+}
+
+/// One line for tmux's `#()` command substitution, with `#` doubled so
+/// tmux does not read it as a format.
+pub struct Tmux;
+
+impl Renderer for Tmux {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let statuses: Vec<Status> = forecasts
+            .iter()
+            .filter_map(|forecast| Status::new(forecast, options))
+            .collect();
+        let line = join(statuses.iter().map(|status| status.text.as_str()));
+        let line: String = line
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect::<String>()
+            .replace('#', "##");
+        writeln!(out, "{}", line)
+    }
+}
+
+/// The first chosen day of a forecast, as short as a status bar needs.
+struct Status {
+    text: String,
+    short_text: String,
+    kind: ConditionKind,
+    stale: bool,
+}
+
+impl Status {
+    fn new(forecast: &LocationForecast, options: &RenderOptions) -> Option<Status> {
+        let (_, daily) = forecast.days().next()?;
+        let details = daily.details().in_units(options.units());
+        let stale = forecast.feed().is_stale();
+        let mut text = format!(
+            "{} {}",
+            daily.summary().summary(),
+            details.temperature_max()
+        );
+        if stale {
+            text.push_str(" (stale)");
+        }
+        Some(Status {
+            text,
+            short_text: String::from(details.temperature_max()),
+            kind: daily.summary().kind(),
+            stale,
+        })
+    }
+}
+
+/// Escapes `text` for waybar, which reads it as Pango markup.
+fn pango(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn join<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    texts.collect::<Vec<_>>().join(SEPARATOR)
+}
+
+/// Every chosen day of every location, a line each, for hovering over.
+fn tooltip(forecasts: &[LocationForecast], options: &RenderOptions) -> String {
+    let mut lines = Vec::new();
+    for forecast in forecasts {
+        lines.push(output::location_name(forecast.channel().get_title()));
+        for (index, daily) in forecast.days() {
+            lines.push(tooltip_line(forecast, index, daily, options));
+        }
+    }
+    lines.join("\n")
+}
+
+fn tooltip_line(
+    forecast: &LocationForecast,
+    index: usize,
+    daily: &Daily,
+    options: &RenderOptions,
+) -> String {
+    let details = daily.details().in_units(options.units());
+    let date =
+        forecast.channel().get_pub_date().date_naive() + chrono::Duration::days(index as i64);
+    format!(
+        "{}: {}, {} / {}, {} {}",
+        date.format("%A"),
+        daily.summary().summary(),
+        details.temperature_max(),
+        details.temperature_min(),
+        details.wind_direction(),
+        details.wind_speed()
+    )
+}
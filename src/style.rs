@@ -0,0 +1,258 @@
+//! Colour for terminal output, using ANSI escape codes.
+//!
+//! Colour is used when stdout is a terminal and the `NO_COLOR` environment
+//! variable is unset or empty, unless `--color` says otherwise.
+
+use serde::{Deserialize, Serialize};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const BLUE: &str = "\x1b[94m";
+const BOLD_RED: &str = "\x1b[1;31m";
+
+/// Wind speeds from Beaufort force 6, a strong breeze, and force 8, a
+/// gale, in mph.
+const STRONG_WIND_MPH: u32 = 25;
+const GALE_MPH: u32 = 39;
+
+/// Whether to use colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// When writing to a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to colour output written to a terminal, if `is_terminal`.
+    /// `Always` and `Never` take precedence over `NO_COLOR`.
+    pub fn enabled(&self, is_terminal: bool) -> bool {
+        self.enabled_with(is_terminal, std::env::var_os("NO_COLOR").as_deref())
+    }
+    /// As `enabled`, with `no_color` as the value of `NO_COLOR`.
+    fn enabled_with(&self, is_terminal: bool, no_color: Option<&std::ffi::OsStr>) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && no_color.map_or(true, |value| value.is_empty()),
+        }
+    }
+}
+
+impl std::str::FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "\"{}\" is not a colour choice, expected auto, always or never",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorChoice::Auto => write!(f, "auto"),
+            ColorChoice::Always => write!(f, "always"),
+            ColorChoice::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// Colours text for what it shows, or leaves it alone if colour is off.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub fn new(enabled: bool) -> Palette {
+        Palette { enabled }
+    }
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled && !text.is_empty() {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            String::from(text)
+        }
+    }
+    /// Dimmed, for labels such as "Wind Speed:".
+    pub fn label(&self, text: &str) -> String {
+        self.paint(DIM, text)
+    }
+    /// Bold, for headings such as the day.
+    pub fn heading(&self, text: &str) -> String {
+        self.paint(BOLD, text)
+    }
+    /// Bold red, for problems such as a stale feed.
+    pub fn warning(&self, text: &str) -> String {
+        self.paint(BOLD_RED, text)
+    }
+    /// From blue for freezing through to red for hot, by `celsius`.
+    pub fn temperature(&self, text: &str, celsius: Option<i32>) -> String {
+        let code = match celsius {
+            Some(c) if c <= 0 => BLUE,
+            Some(c) if c < 10 => CYAN,
+            Some(c) if c < 20 => GREEN,
+            Some(c) if c < 27 => YELLOW,
+            Some(_) => RED,
+            None => return String::from(text),
+        };
+        self.paint(code, text)
+    }
+    /// Yellow for a strong breeze and bold red for a gale, by `mph`.
+    pub fn wind(&self, text: &str, mph: Option<u32>) -> String {
+        match mph {
+            Some(mph) if mph >= GALE_MPH => self.paint(BOLD_RED, text),
+            Some(mph) if mph >= STRONG_WIND_MPH => self.paint(YELLOW, text),
+            _ => String::from(text),
+        }
+    }
+    /// Yellow for a high UV risk index and bold red for very high or worse.
+    pub fn uv_risk(&self, text: &str, index: Option<u32>) -> String {
+        match index {
+            Some(index) if index >= 8 => self.paint(BOLD_RED, text),
+            Some(index) if index >= 6 => self.paint(YELLOW, text),
+            _ => String::from(text),
+        }
+    }
+    /// Yellow for moderate pollution and bold red for high or very high.
+    pub fn pollution(&self, text: &str) -> String {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "moderate" => self.paint(YELLOW, text),
+            "high" | "very high" => self.paint(BOLD_RED, text),
+            _ => String::from(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    const ON: Palette = Palette { enabled: true };
+    const OFF: Palette = Palette { enabled: false };
+
+    #[test]
+    fn auto_colours_only_terminals_without_no_color() {
+        let auto = ColorChoice::Auto;
+        assert!(auto.enabled_with(true, None));
+        assert!(auto.enabled_with(true, Some(OsStr::new(""))));
+        assert!(!auto.enabled_with(true, Some(OsStr::new("1"))));
+        // Colour is dropped when stdout is piped or redirected.
+        assert!(!auto.enabled_with(false, None));
+    }
+
+    #[test]
+    fn always_and_never_override_the_terminal_and_no_color() {
+        for (is_terminal, no_color) in [(true, None), (false, None), (true, Some(OsStr::new("1")))]
+        {
+            assert!(ColorChoice::Always.enabled_with(is_terminal, no_color));
+            assert!(!ColorChoice::Never.enabled_with(is_terminal, no_color));
+        }
+    }
+
+    #[test]
+    fn parses_color_choices() {
+        assert_eq!("always".parse(), Ok(ColorChoice::Always));
+        assert_eq!("NEVER".parse(), Ok(ColorChoice::Never));
+        assert_eq!("auto".parse(), Ok(ColorChoice::Auto));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+        assert_eq!(ColorChoice::default(), ColorChoice::Auto);
+    }
+
+    #[test]
+    fn a_disabled_palette_leaves_text_alone() {
+        assert_eq!(OFF.temperature("30°C", Some(30)), "30°C");
+        assert_eq!(OFF.wind("50mph", Some(50)), "50mph");
+        assert_eq!(OFF.uv_risk("9", Some(9)), "9");
+        assert_eq!(OFF.pollution("High"), "High");
+        assert_eq!(OFF.warning("stale"), "stale");
+        assert_eq!(ON.heading(""), "");
+    }
+
+    #[test]
+    fn temperatures_go_from_blue_to_red() {
+        for (celsius, code) in [
+            (-5, BLUE),
+            (0, BLUE),
+            (1, CYAN),
+            (9, CYAN),
+            (10, GREEN),
+            (19, GREEN),
+            (20, YELLOW),
+            (26, YELLOW),
+            (27, RED),
+        ] {
+            assert_eq!(
+                ON.temperature("t", Some(celsius)),
+                format!("{}t{}", code, RESET),
+                "{}°C",
+                celsius
+            );
+        }
+        assert_eq!(ON.temperature("t", None), "t");
+    }
+
+    #[test]
+    fn strong_winds_and_gales_stand_out() {
+        assert_eq!(ON.wind("w", Some(STRONG_WIND_MPH - 1)), "w");
+        assert_eq!(
+            ON.wind("w", Some(STRONG_WIND_MPH)),
+            format!("{}w{}", YELLOW, RESET)
+        );
+        assert_eq!(
+            ON.wind("w", Some(GALE_MPH - 1)),
+            format!("{}w{}", YELLOW, RESET)
+        );
+        assert_eq!(
+            ON.wind("w", Some(GALE_MPH)),
+            format!("{}w{}", BOLD_RED, RESET)
+        );
+        assert_eq!(ON.wind("w", None), "w");
+    }
+
+    #[test]
+    fn high_uv_stands_out() {
+        assert_eq!(ON.uv_risk("u", Some(5)), "u");
+        assert_eq!(ON.uv_risk("u", Some(6)), format!("{}u{}", YELLOW, RESET));
+        assert_eq!(ON.uv_risk("u", Some(7)), format!("{}u{}", YELLOW, RESET));
+        assert_eq!(ON.uv_risk("u", Some(8)), format!("{}u{}", BOLD_RED, RESET));
+        assert_eq!(ON.uv_risk("u", None), "u");
+    }
+
+    #[test]
+    fn pollution_is_coloured_by_its_band() {
+        assert_eq!(ON.pollution("Low"), "Low");
+        assert_eq!(
+            ON.pollution("Moderate"),
+            format!("{}Moderate{}", YELLOW, RESET)
+        );
+        assert_eq!(
+            ON.pollution(" high "),
+            format!("{} high {}", BOLD_RED, RESET)
+        );
+        assert_eq!(
+            ON.pollution("Very High"),
+            format!("{}Very High{}", BOLD_RED, RESET)
+        );
+    }
+}
@@ -0,0 +1,446 @@
+//! A table of forecast days side by side, with a row for each detail, to
+//! compare days at a glance:
+//!
+//! ```text
+//! ┌─────────────────────┬─────────────────┬───────────────┐
+//! │                     │ Today           │ Tomorrow      │
+//! ├─────────────────────┼─────────────────┼───────────────┤
+//! │ Summary             │ Sunny Intervals │ Light Rain    │
+//! │ Maximum Temperature │ 15°C (59°F)     │ 14°C (57°F)   │
+//! └─────────────────────┴─────────────────┴───────────────┘
+//! ```
+//!
+//! Tables are drawn with box-drawing characters, or ASCII where the locale
+//! is not UTF-8. A table wider than `RenderOptions::width()` first switches
+//! to short labels and compass points, then to short day names without
+//! icons, and then wraps its cells, splitting as few words as it can.
+
+use std::io::{self, Write};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::icon::Icons;
+use crate::model::{Daily, Details};
+use crate::render::{self, RenderOptions, Renderer};
+use crate::style::Palette;
+use crate::template;
+use crate::LocationForecast;
+
+/// Columns are never wrapped narrower than this.
+const MIN_WIDTH: usize = 4;
+
+/// The characters a table is drawn with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Borders {
+    /// Box-drawing characters.
+    #[default]
+    Unicode,
+    /// `+`, `-` and `|`, for terminals and fonts without box drawing.
+    Ascii,
+}
+
+impl Borders {
+    /// Unicode when `LC_ALL`, `LC_CTYPE` or `LANG`, whichever is set first,
+    /// names UTF-8, and ASCII otherwise.
+    pub fn detect() -> Borders {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            Borders::Unicode
+        } else {
+            Borders::Ascii
+        }
+    }
+    /// The left, middle, right and horizontal characters of a rule: the
+    /// top, below the heading or the bottom.
+    fn rule(&self, rule: Rule) -> [char; 4] {
+        match (self, rule) {
+            (Borders::Unicode, Rule::Top) => ['┌', '┬', '┐', '─'],
+            (Borders::Unicode, Rule::Middle) => ['├', '┼', '┤', '─'],
+            (Borders::Unicode, Rule::Bottom) => ['└', '┴', '┘', '─'],
+            (Borders::Ascii, Rule::Middle) => ['+', '+', '+', '='],
+            (Borders::Ascii, _) => ['+', '+', '+', '-'],
+        }
+    }
+    fn vertical(&self) -> char {
+        match self {
+            Borders::Unicode => '│',
+            Borders::Ascii => '|',
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Rule {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Days as columns and details as rows.
+pub struct Table;
+
+impl Renderer for Table {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let palette = options.palette();
+        for forecast in forecasts {
+            if forecasts.len() > 1 {
+                render::write_field(out, palette, "Location", forecast.location())?;
+            }
+            render::write_stale(out, palette, forecast.feed())?;
+            let days: Vec<(String, &Daily)> = forecast
+                .days()
+                .filter_map(|(index, daily)| Some((render::day_name(forecast, index)?, daily)))
+                .collect();
+            let icons = options.icons();
+            let mut grid = Grid::new(&days, options, icons, false);
+            if let Some(width) = options.width() {
+                if grid.width() > width {
+                    grid = Grid::new(&days, options, icons, true);
+                }
+                if grid.width() > width {
+                    let days: Vec<(String, &Daily)> = forecast
+                        .days()
+                        .map(|(index, daily)| (short_day_name(forecast, index), daily))
+                        .collect();
+                    grid = Grid::new(&days, options, Icons::None, true);
+                    grid.fit(width);
+                }
+            }
+            grid.write(out, options.borders(), palette)?;
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// The day of the week, such as "Mon", for day `index` of `forecast`.
+fn short_day_name(forecast: &LocationForecast, index: usize) -> String {
+    let date =
+        forecast.channel().get_pub_date().date_naive() + chrono::Duration::days(index as i64);
+    date.format("%a").to_string()
+}
+
+/// How a cell is coloured, by what it shows.
+#[derive(Clone, Copy)]
+enum Paint {
+    Heading,
+    Label,
+    None,
+    Temperature(Option<i32>),
+    Wind(Option<u32>),
+    UvRisk(Option<u32>),
+    Pollution,
+}
+
+impl Paint {
+    fn apply(&self, palette: Palette, text: &str) -> String {
+        match *self {
+            Paint::Heading => palette.heading(text),
+            Paint::Label => palette.label(text),
+            Paint::None => String::from(text),
+            Paint::Temperature(celsius) => palette.temperature(text, celsius),
+            Paint::Wind(mph) => palette.wind(text, mph),
+            Paint::UvRisk(index) => palette.uv_risk(text, index),
+            Paint::Pollution => palette.pollution(text),
+        }
+    }
+}
+
+struct Cell {
+    text: String,
+    paint: Paint,
+    /// Lines drawn as they are rather than wrapped, for icons.
+    art: bool,
+}
+
+impl Cell {
+    fn new(text: &str, paint: Paint) -> Cell {
+        Cell {
+            text: String::from(text),
+            paint,
+            art: false,
+        }
+    }
+    fn art(lines: &[&str]) -> Cell {
+        Cell {
+            text: lines.join("\n"),
+            paint: Paint::None,
+            art: true,
+        }
+    }
+    fn width(&self) -> usize {
+        self.text
+            .lines()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0)
+    }
+    /// How many words would be split to wrap the cell to `width`.
+    fn split_words(&self, width: usize) -> usize {
+        if self.art {
+            return 0;
+        }
+        self.text
+            .split_whitespace()
+            .filter(|word| word.width() > width)
+            .count()
+    }
+    /// The lines of the cell, wrapped to `width` unless it is art.
+    fn lines(&self, width: usize) -> Vec<String> {
+        if self.art {
+            self.text
+                .lines()
+                .map(|line| line.trim_end().to_string())
+                .collect()
+        } else {
+            wrap(&self.text, width)
+        }
+    }
+}
+
+/// The cells of a table, a row at a time, and the width of each column.
+struct Grid {
+    rows: Vec<Vec<Cell>>,
+    widths: Vec<usize>,
+}
+
+impl Grid {
+    /// The heading row of day names, then the `icons` if any, then a row for
+    /// each detail. Labels are shortened, and wind directions given as
+    /// compass points, if `short`.
+    fn new(days: &[(String, &Daily)], options: &RenderOptions, icons: Icons, short: bool) -> Grid {
+        let label = |long: &str, abbreviated: &str| {
+            Cell::new(if short { abbreviated } else { long }, Paint::Label)
+        };
+        let mut rows = vec![std::iter::once(Cell::new("", Paint::None))
+            .chain(days.iter().map(|(name, _)| Cell::new(name, Paint::Heading)))
+            .collect::<Vec<_>>()];
+        if icons != Icons::None {
+            rows.push(
+                std::iter::once(Cell::new("", Paint::None))
+                    .chain(
+                        days.iter()
+                            .map(|(_, daily)| Cell::art(icons.icon(daily.summary().kind()))),
+                    )
+                    .collect(),
+            );
+        }
+        rows.push(
+            std::iter::once(label("Summary", "Summary"))
+                .chain(
+                    days.iter()
+                        .map(|(_, daily)| Cell::new(daily.summary().summary(), Paint::None)),
+                )
+                .collect(),
+        );
+        // Each cell is given the details as published and in the chosen units.
+        type Value = fn(&Details, &Details, bool) -> Cell;
+        let details: [(&str, &str, Value); 11] = [
+            ("Maximum Temperature", "Max", |raw, shown, _| {
+                Cell::new(
+                    shown.temperature_max(),
+                    Paint::Temperature(raw.temperature_max_celsius()),
+                )
+            }),
+            ("Minimum Temperature", "Min", |raw, shown, _| {
+                Cell::new(
+                    shown.temperature_min(),
+                    Paint::Temperature(raw.temperature_min_celsius()),
+                )
+            }),
+            ("Wind Direction", "Wind", |_, shown, short| {
+                if short {
+                    Cell::new(
+                        &template::compass_point(shown.wind_direction()),
+                        Paint::None,
+                    )
+                } else {
+                    Cell::new(shown.wind_direction(), Paint::None)
+                }
+            }),
+            ("Wind Speed", "Speed", |raw, shown, _| {
+                Cell::new(shown.wind_speed(), Paint::Wind(raw.wind_speed_mph()))
+            }),
+            ("Visibility", "Vis", |_, shown, _| {
+                Cell::new(shown.visibility(), Paint::None)
+            }),
+            ("Pressure", "Press", |_, shown, _| {
+                Cell::new(shown.pressure(), Paint::None)
+            }),
+            ("Humidity", "Hum", |_, shown, _| {
+                Cell::new(shown.humidity(), Paint::None)
+            }),
+            ("UV Risk", "UV", |raw, shown, _| {
+                Cell::new(shown.uv_risk(), Paint::UvRisk(raw.uv_risk_index()))
+            }),
+            ("Pollution", "Poll", |_, shown, _| {
+                Cell::new(shown.pollution_level(), Paint::Pollution)
+            }),
+            ("Sunrise", "Rise", |_, shown, _| {
+                Cell::new(shown.sunrise_time(), Paint::None)
+            }),
+            ("Sunset", "Set", |_, shown, _| {
+                Cell::new(shown.sunset_time(), Paint::None)
+            }),
+        ];
+        for (long, abbreviated, value) in details.iter() {
+            let mut row = vec![label(long, abbreviated)];
+            for (_, daily) in days {
+                let raw = daily.details();
+                row.push(value(raw, &raw.in_units(options.units()), short));
+            }
+            rows.push(row);
+        }
+        let widths = (0..=days.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        Grid { rows, widths }
+    }
+
+    /// The width of the whole table, borders and padding included.
+    fn width(&self) -> usize {
+        self.widths.iter().sum::<usize>() + 3 * self.widths.len() + 1
+    }
+
+    /// Narrows a column a character at a time until the table is no wider
+    /// than `width`, stopping when every column is as narrow as it can be.
+    /// Each time it narrows the column that splits the fewest more words,
+    /// preferring days to the labels, then the widest.
+    fn fit(&mut self, width: usize) {
+        while self.width() > width {
+            let column = (0..self.widths.len())
+                .filter(|column| self.widths[*column] > MIN_WIDTH)
+                .min_by_key(|column| {
+                    let narrower = self.widths[*column] - 1;
+                    let splits = |width| {
+                        self.rows
+                            .iter()
+                            .map(|row| row[*column].split_words(width))
+                            .sum::<usize>()
+                    };
+                    (
+                        splits(narrower) - splits(narrower + 1),
+                        *column == 0,
+                        std::cmp::Reverse(self.widths[*column]),
+                        *column,
+                    )
+                });
+            match column {
+                Some(column) => self.widths[column] -= 1,
+                None => return,
+            }
+        }
+    }
+
+    fn write(&self, out: &mut dyn Write, borders: Borders, palette: Palette) -> io::Result<()> {
+        self.write_rule(out, borders, Rule::Top)?;
+        for (index, row) in self.rows.iter().enumerate() {
+            self.write_row(out, borders, palette, row)?;
+            if index == 0 {
+                self.write_rule(out, borders, Rule::Middle)?;
+            }
+        }
+        self.write_rule(out, borders, Rule::Bottom)
+    }
+
+    fn write_rule(&self, out: &mut dyn Write, borders: Borders, rule: Rule) -> io::Result<()> {
+        let [left, middle, right, horizontal] = borders.rule(rule);
+        let line: Vec<String> = self
+            .widths
+            .iter()
+            .map(|width| horizontal.to_string().repeat(width + 2))
+            .collect();
+        writeln!(out, "{}{}{}", left, line.join(&middle.to_string()), right)
+    }
+
+    /// Writes a row, as many lines tall as its most wrapped cell.
+    fn write_row(
+        &self,
+        out: &mut dyn Write,
+        borders: Borders,
+        palette: Palette,
+        row: &[Cell],
+    ) -> io::Result<()> {
+        let wrapped: Vec<Vec<String>> = row
+            .iter()
+            .zip(&self.widths)
+            .map(|(cell, width)| cell.lines(*width))
+            .collect();
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+        let vertical = borders.vertical();
+        for line in 0..height {
+            write!(out, "{}", vertical)?;
+            for ((cell, lines), width) in row.iter().zip(&wrapped).zip(&self.widths) {
+                let text = lines.get(line).map(String::as_str).unwrap_or("");
+                let padding = width.saturating_sub(text.width());
+                write!(
+                    out,
+                    " {}{} {}",
+                    cell.paint.apply(palette, text),
+                    " ".repeat(padding),
+                    vertical
+                )?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits `text` into lines no wider than `width`, between words where it
+/// can and within words longer than a line.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word;
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.width() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let split = split_at_width(word, width);
+            lines.push(String::from(&word[..split]));
+            word = &word[split..];
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// The byte index of the last character boundary at most `width` wide,
+/// but after at least one character.
+fn split_at_width(word: &str, width: usize) -> usize {
+    let mut end = 0;
+    for (index, c) in word.char_indices() {
+        let next = index + c.len_utf8();
+        if word[..next].width() > width && end > 0 {
+            break;
+        }
+        end = next;
+    }
+    end
+}
@@ -0,0 +1,600 @@
+//! Output templates such as `"{day}: {condition}, {min}–{max}, wind {wind_dir}
+//! {wind_speed}"`, written once for each day of each forecast.
+//!
+//! A placeholder is a name in braces, optionally followed by a colon, a unit
+//! and a precision: `{max:f}`, `{wind_speed:kmh.1}` or `{mean:.1}`. Without
+//! a unit, values follow the chosen `Units`. Use `{{` and `}}` for literal
+//! braces. See `PLACEHOLDERS` for every name.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use chrono::{Duration, NaiveTime};
+
+use crate::error::Error;
+use crate::model::{Daily, Details, KM_PER_MILE};
+use crate::output;
+use crate::render::{RenderOptions, Renderer};
+use crate::units::Units;
+use crate::LocationForecast;
+
+const METRES_PER_SECOND_PER_MPH: f64 = 0.44704;
+const KNOTS_PER_MPH: f64 = 0.868976;
+const INCHES_OF_MERCURY_PER_MB: f64 = 0.02953;
+
+/// Beaufort force 1 to 12 start at these wind speeds, in mph.
+const BEAUFORT_MPH: [u32; 12] = [1, 4, 8, 13, 19, 25, 32, 39, 47, 55, 64, 73];
+
+/// What a value is missing from the feed is shown as.
+const MISSING: &str = "--";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Placeholder {
+    Location,
+    Name,
+    Link,
+    Language,
+    Issued,
+    Fetched,
+    Stale,
+    Day,
+    Index,
+    Date,
+    Weekday,
+    Condition,
+    Max,
+    Min,
+    Mean,
+    Range,
+    WindDir,
+    WindDirShort,
+    WindSpeed,
+    Beaufort,
+    Visibility,
+    Pressure,
+    Humidity,
+    Uv,
+    Pollution,
+    Sunrise,
+    Sunset,
+    Daylight,
+}
+
+/// Every placeholder with what it stands for. Units a placeholder accepts
+/// are listed in brackets.
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("location", "the location as it was asked for"),
+    (
+        "name",
+        "the place the forecast is for, such as \"London, GB\"",
+    ),
+    ("link", "the BBC Weather page for the location"),
+    ("language", "the feed's language"),
+    (
+        "issued",
+        "when the forecast was issued, such as 2026-10-18 05:00",
+    ),
+    ("fetched", "when the feed was fetched, in UTC"),
+    (
+        "stale",
+        "\"stale\" if a cached feed is shown because BBC Weather could not be reached",
+    ),
+    (
+        "day",
+        "Today, Tomorrow, Overmorrow, or the day asked for with --day",
+    ),
+    (
+        "index",
+        "how many days after the forecast was issued, from 0",
+    ),
+    ("date", "the date, such as 2026-10-18"),
+    ("weekday", "the day of the week"),
+    ("condition", "the weather, such as \"Sunny Intervals\""),
+    ("max", "the maximum temperature [c, f]"),
+    ("min", "the minimum temperature [c, f]"),
+    ("mean", "halfway between the maximum and minimum [c, f]"),
+    ("range", "the maximum less the minimum [c, f]"),
+    ("wind_dir", "the wind direction, such as \"South Westerly\""),
+    (
+        "wind_dir_short",
+        "the wind direction as a compass point, such as SW",
+    ),
+    ("wind_speed", "the wind speed [mph, kmh, ms, kn]"),
+    ("beaufort", "the wind force on the Beaufort scale, 0 to 12"),
+    ("visibility", "the visibility, such as \"Good\""),
+    ("pressure", "the air pressure [mb, hpa, inhg]"),
+    ("humidity", "the relative humidity, such as 71%"),
+    ("uv", "the UV risk index"),
+    ("pollution", "the pollution level, such as \"Low\""),
+    ("sunrise", "the time of sunrise"),
+    ("sunset", "the time of sunset"),
+    (
+        "daylight",
+        "the time from sunrise to sunset, such as 10h39m",
+    ),
+];
+
+impl Placeholder {
+    fn from_name(name: &str) -> Option<Placeholder> {
+        let placeholder = match name {
+            "location" => Placeholder::Location,
+            "name" => Placeholder::Name,
+            "link" => Placeholder::Link,
+            "language" => Placeholder::Language,
+            "issued" => Placeholder::Issued,
+            "fetched" => Placeholder::Fetched,
+            "stale" => Placeholder::Stale,
+            "day" => Placeholder::Day,
+            "index" => Placeholder::Index,
+            "date" => Placeholder::Date,
+            "weekday" => Placeholder::Weekday,
+            "condition" => Placeholder::Condition,
+            "max" => Placeholder::Max,
+            "min" => Placeholder::Min,
+            "mean" => Placeholder::Mean,
+            "range" => Placeholder::Range,
+            "wind_dir" => Placeholder::WindDir,
+            "wind_dir_short" => Placeholder::WindDirShort,
+            "wind_speed" => Placeholder::WindSpeed,
+            "beaufort" => Placeholder::Beaufort,
+            "visibility" => Placeholder::Visibility,
+            "pressure" => Placeholder::Pressure,
+            "humidity" => Placeholder::Humidity,
+            "uv" => Placeholder::Uv,
+            "pollution" => Placeholder::Pollution,
+            "sunrise" => Placeholder::Sunrise,
+            "sunset" => Placeholder::Sunset,
+            "daylight" => Placeholder::Daylight,
+            _ => return None,
+        };
+        Some(placeholder)
+    }
+    /// The units this placeholder can be shown in, if it is a measurement.
+    fn units(&self) -> &'static [(&'static str, Unit)] {
+        match self {
+            Placeholder::Max | Placeholder::Min | Placeholder::Mean | Placeholder::Range => {
+                &[("c", Unit::Celsius), ("f", Unit::Fahrenheit)]
+            }
+            Placeholder::WindSpeed => &[
+                ("mph", Unit::Mph),
+                ("kmh", Unit::Kmh),
+                ("ms", Unit::MetresPerSecond),
+                ("kn", Unit::Knots),
+            ],
+            Placeholder::Pressure => &[
+                ("mb", Unit::Millibars),
+                ("hpa", Unit::Hectopascals),
+                ("inhg", Unit::InchesOfMercury),
+            ],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+    Mph,
+    Kmh,
+    MetresPerSecond,
+    Knots,
+    Millibars,
+    Hectopascals,
+    InchesOfMercury,
+}
+
+impl Unit {
+    fn default_precision(&self) -> usize {
+        match self {
+            Unit::InchesOfMercury => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// A measurement in the units BBC Weather publishes it in.
+#[derive(Clone, Copy, Debug)]
+enum Quantity {
+    Celsius(f64),
+    /// A difference between temperatures, which converts without an offset.
+    CelsiusDifference(f64),
+    Mph(f64),
+    Millibars(f64),
+}
+
+impl Quantity {
+    fn format(&self, unit: Unit, precision: usize) -> String {
+        let (value, suffix) = match (self, unit) {
+            (Quantity::Celsius(c), Unit::Celsius) => (*c, "°C"),
+            (Quantity::Celsius(c), Unit::Fahrenheit) => (c * 9.0 / 5.0 + 32.0, "°F"),
+            (Quantity::CelsiusDifference(c), Unit::Celsius) => (*c, "°C"),
+            (Quantity::CelsiusDifference(c), Unit::Fahrenheit) => (c * 9.0 / 5.0, "°F"),
+            (Quantity::Mph(mph), Unit::Mph) => (*mph, "mph"),
+            (Quantity::Mph(mph), Unit::Kmh) => (mph * KM_PER_MILE, "km/h"),
+            (Quantity::Mph(mph), Unit::MetresPerSecond) => (mph * METRES_PER_SECOND_PER_MPH, "m/s"),
+            (Quantity::Mph(mph), Unit::Knots) => (mph * KNOTS_PER_MPH, "kn"),
+            (Quantity::Millibars(mb), Unit::Millibars) => (*mb, "mb"),
+            (Quantity::Millibars(mb), Unit::Hectopascals) => (*mb, "hPa"),
+            (Quantity::Millibars(mb), Unit::InchesOfMercury) => {
+                (mb * INCHES_OF_MERCURY_PER_MB, "inHg")
+            }
+            (quantity, unit) => unreachable!("{:?} cannot be shown in {:?}", quantity, unit),
+        };
+        format!("{:.*}{}", precision, value, suffix)
+    }
+    /// The units to show this in when the template does not say, following
+    /// `units` as the other renderers do.
+    fn default_units(&self, units: Units) -> &'static [Unit] {
+        match (self, units) {
+            (Quantity::Celsius(_), Units::Metric)
+            | (Quantity::CelsiusDifference(_), Units::Metric) => &[Unit::Celsius],
+            (Quantity::Celsius(_), Units::Imperial)
+            | (Quantity::CelsiusDifference(_), Units::Imperial) => &[Unit::Fahrenheit],
+            (Quantity::Celsius(_), Units::Both) | (Quantity::CelsiusDifference(_), Units::Both) => {
+                &[Unit::Celsius, Unit::Fahrenheit]
+            }
+            (Quantity::Mph(_), Units::Metric) => &[Unit::Kmh],
+            (Quantity::Mph(_), _) => &[Unit::Mph],
+            (Quantity::Millibars(_), _) => &[Unit::Millibars],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Field {
+    placeholder: Placeholder,
+    unit: Option<Unit>,
+    precision: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// A parsed output template, which renders a line for each day.
+#[derive(Clone, Debug)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+impl std::str::FromStr for Template {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Template::parse(template)
+    }
+}
+
+impl Template {
+    /// Parses `template`, failing with `Error::Template` on an unknown
+    /// placeholder, a unit it cannot be shown in, or an unmatched brace.
+    pub fn parse(template: &str) -> Result<Template, Error> {
+        let error = |index: usize, reason: String| Error::Template {
+            template: String::from(template),
+            column: template[..index].chars().count() + 1,
+            reason,
+        };
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => {
+                    return Err(error(
+                        index,
+                        String::from("'}' without a matching '{', write }} for a brace"),
+                    ))
+                }
+                '{' => {
+                    let end = match template[index..].find('}') {
+                        Some(end) => index + end,
+                        None => {
+                            return Err(error(
+                                index,
+                                String::from("'{' is never closed, write {{ for a brace"),
+                            ))
+                        }
+                    };
+                    let field = Field::parse(&template[index + 1..end])
+                        .map_err(|reason| error(index, reason))?;
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                    while let Some((next, _)) = chars.peek() {
+                        if *next > end {
+                            break;
+                        }
+                        chars.next();
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Template { pieces })
+    }
+    /// The template filled in for `daily`, day `index` of `forecast`.
+    pub fn render(
+        &self,
+        forecast: &LocationForecast,
+        index: usize,
+        daily: &Daily,
+        options: &RenderOptions,
+    ) -> String {
+        let mut line = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => line.push_str(text),
+                Piece::Field(field) => {
+                    let _ = write!(line, "{}", field.value(forecast, index, daily, options));
+                }
+            }
+        }
+        line
+    }
+}
+
+impl Renderer for Template {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        for forecast in forecasts {
+            for (index, daily) in forecast.days() {
+                writeln!(
+                    out,
+                    "{}",
+                    Template::render(self, forecast, index, daily, options)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Field {
+    fn parse(field: &str) -> Result<Field, String> {
+        let (name, modifiers) = match field.split_once(':') {
+            Some((name, modifiers)) => (name.trim(), Some(modifiers.trim())),
+            None => (field.trim(), None),
+        };
+        let placeholder = Placeholder::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = PLACEHOLDERS.iter().map(|(name, _)| *name).collect();
+            format!(
+                "unknown placeholder {{{}}}, expected one of {}",
+                name,
+                names.join(", ")
+            )
+        })?;
+        let mut parsed = Field {
+            placeholder,
+            unit: None,
+            precision: None,
+        };
+        let modifiers = match modifiers {
+            Some(modifiers) => modifiers,
+            None => return Ok(parsed),
+        };
+        let units = placeholder.units();
+        if units.is_empty() {
+            return Err(format!("{{{}}} does not take a unit or precision", name));
+        }
+        let (unit, precision) = match modifiers.split_once('.') {
+            Some((unit, precision)) => (unit, Some(precision)),
+            None => (modifiers, None),
+        };
+        if !unit.is_empty() {
+            let unit = unit.to_ascii_lowercase();
+            match units.iter().find(|(name, _)| *name == unit) {
+                Some((_, unit)) => parsed.unit = Some(*unit),
+                None => {
+                    let names: Vec<&str> = units.iter().map(|(name, _)| *name).collect();
+                    return Err(format!(
+                        "{{{}}} cannot be shown in \"{}\", expected {}",
+                        name,
+                        unit,
+                        names.join(", ")
+                    ));
+                }
+            }
+        }
+        if let Some(precision) = precision {
+            match precision.parse::<usize>() {
+                Ok(precision) if precision <= 6 => parsed.precision = Some(precision),
+                _ => {
+                    return Err(format!(
+                        "\"{}\" is not a precision, expected 0 to 6 decimal places",
+                        precision
+                    ))
+                }
+            }
+        }
+        Ok(parsed)
+    }
+    fn value(
+        &self,
+        forecast: &LocationForecast,
+        index: usize,
+        daily: &Daily,
+        options: &RenderOptions,
+    ) -> String {
+        let details = daily.details();
+        let channel = forecast.channel();
+        let issued = channel.get_pub_date();
+        let date = issued.date_naive() + Duration::days(index as i64);
+        match self.placeholder {
+            Placeholder::Location => String::from(forecast.location()),
+            Placeholder::Name => output::location_name(channel.get_title()),
+            Placeholder::Link => channel.get_link().to_string(),
+            Placeholder::Language => String::from(channel.get_language()),
+            Placeholder::Issued => issued.format("%Y-%m-%d %H:%M").to_string(),
+            Placeholder::Fetched => forecast
+                .feed()
+                .fetched()
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            Placeholder::Stale => String::from(if forecast.feed().is_stale() {
+                "stale"
+            } else {
+                ""
+            }),
+            Placeholder::Day => match forecast.day() {
+                Some(day) => day.to_string(),
+                None => match index {
+                    0 => String::from("Today"),
+                    1 => String::from("Tomorrow"),
+                    2 => String::from("Overmorrow"),
+                    _ => date.format("%A").to_string(),
+                },
+            },
+            Placeholder::Index => index.to_string(),
+            Placeholder::Date => date.format("%Y-%m-%d").to_string(),
+            Placeholder::Weekday => date.format("%A").to_string(),
+            Placeholder::Condition => String::from(daily.summary().summary()),
+            Placeholder::WindDir => String::from(details.wind_direction()),
+            Placeholder::WindDirShort => compass_point(details.wind_direction()),
+            Placeholder::Beaufort => match details.wind_speed_mph() {
+                Some(mph) => beaufort(mph).to_string(),
+                None => String::from(MISSING),
+            },
+            Placeholder::Visibility => String::from(details.visibility()),
+            Placeholder::Humidity => String::from(details.humidity()),
+            Placeholder::Uv => String::from(details.uv_risk()),
+            Placeholder::Pollution => String::from(details.pollution_level()),
+            Placeholder::Sunrise => String::from(details.sunrise_time()),
+            Placeholder::Sunset => String::from(details.sunset_time()),
+            Placeholder::Daylight => daylight(details),
+            Placeholder::Max
+            | Placeholder::Min
+            | Placeholder::Mean
+            | Placeholder::Range
+            | Placeholder::WindSpeed
+            | Placeholder::Pressure => self.measurement(details, options),
+        }
+    }
+    fn measurement(&self, details: &Details, options: &RenderOptions) -> String {
+        if self.unit.is_none() && self.precision.is_none() {
+            // Shown exactly as BBC Weather publishes them.
+            let details = details.in_units(options.units());
+            match self.placeholder {
+                Placeholder::Max => return String::from(details.temperature_max()),
+                Placeholder::Min => return String::from(details.temperature_min()),
+                Placeholder::WindSpeed => return String::from(details.wind_speed()),
+                Placeholder::Pressure => return String::from(details.pressure()),
+                _ => (),
+            }
+        }
+        let max = details.temperature_max_celsius().map(f64::from);
+        let min = details.temperature_min_celsius().map(f64::from);
+        let quantity = match self.placeholder {
+            Placeholder::Max => max.map(Quantity::Celsius),
+            Placeholder::Min => min.map(Quantity::Celsius),
+            Placeholder::Mean => max
+                .zip(min)
+                .map(|(max, min)| Quantity::Celsius((max + min) / 2.0)),
+            Placeholder::Range => max
+                .zip(min)
+                .map(|(max, min)| Quantity::CelsiusDifference(max - min)),
+            Placeholder::WindSpeed => details
+                .wind_speed_mph()
+                .map(|mph| Quantity::Mph(mph.into())),
+            Placeholder::Pressure => details
+                .pressure_mb()
+                .map(|mb| Quantity::Millibars(mb.into())),
+            _ => None,
+        };
+        let quantity = match quantity {
+            Some(quantity) => quantity,
+            None => return String::from(MISSING),
+        };
+        let units = match &self.unit {
+            Some(unit) => std::slice::from_ref(unit),
+            None => quantity.default_units(options.units()),
+        };
+        let mut values = units.iter().map(|unit| {
+            quantity.format(
+                *unit,
+                self.precision.unwrap_or_else(|| unit.default_precision()),
+            )
+        });
+        let first = values.next().unwrap_or_default();
+        match values.next() {
+            Some(second) => format!("{} ({})", first, second),
+            None => first,
+        }
+    }
+}
+
+/// The Beaufort force, from 0 to 12, of a wind speed in mph.
+pub(crate) fn beaufort(mph: u32) -> usize {
+    BEAUFORT_MPH
+        .iter()
+        .take_while(|start| mph >= **start)
+        .count()
+}
+
+/// "SW" for "South Westerly" and "NNE" for "North North Easterly".
+pub(crate) fn compass_point(direction: &str) -> String {
+    let point: String = direction
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if point.is_empty() {
+        String::from(MISSING)
+    } else {
+        point
+    }
+}
+
+/// The time from sunrise to sunset, from times such as "07:23 BST".
+fn daylight(details: &Details) -> String {
+    let time = |value: &str| NaiveTime::parse_from_str(value.get(..5)?, "%H:%M").ok();
+    match (time(details.sunrise_time()), time(details.sunset_time())) {
+        (Some(sunrise), Some(sunset)) if sunset > sunrise => {
+            let minutes = (sunset - sunrise).num_minutes();
+            format!("{}h{:02}m", minutes / 60, minutes % 60)
+        }
+        _ => String::from(MISSING),
+    }
+}
+
+/// Every placeholder and its units, for help text.
+pub fn help() -> String {
+    let width = PLACEHOLDERS
+        .iter()
+        .map(|(name, _)| name.len() + 2)
+        .max()
+        .unwrap_or(0);
+    let mut help = String::from(
+        "A line to write for each day, such as \"{day}: {condition}, {min}-{max}\". \
+         Placeholders may take a unit and a precision, as in {max:f} or \
+         {wind_speed:ms.1}; write {{ and }} for braces. Placeholders:\n",
+    );
+    for (name, description) in PLACEHOLDERS {
+        let _ = writeln!(
+            help,
+            "    {:width$}  {}",
+            format!("{{{}}}", name),
+            description,
+            width = width
+        );
+    }
+    help
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Which units temperatures and wind speeds are shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// Celsius and kilometres per hour.
+    Metric,
+    /// Fahrenheit and miles per hour.
+    Imperial,
+    /// As BBC Weather publishes them: both temperatures and miles per hour.
+    #[default]
+    Both,
+}
+
+impl std::str::FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "metric" => Ok(Units::Metric),
+            "imperial" => Ok(Units::Imperial),
+            "both" => Ok(Units::Both),
+            _ => Err(format!(
+                "\"{}\" is not a kind of units, expected metric, imperial or both",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Units {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Units::Metric => write!(f, "metric"),
+            Units::Imperial => write!(f, "imperial"),
+            Units::Both => write!(f, "both"),
+        }
+    }
+}
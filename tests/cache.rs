@@ -0,0 +1,240 @@
+mod common;
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use chrono::Utc;
+use common::{temp_dir, FORECAST, LONDON};
+use text_weather::cache::{Cache, CachingFetcher};
+use text_weather::error::Error;
+use text_weather::fetcher::{Feed, Fetcher};
+use url::Url;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Answers every fetch with `reply`, counting how often it was asked.
+struct Scripted {
+    reply: fn() -> Result<Feed, Error>,
+    calls: Cell<usize>,
+}
+
+impl Scripted {
+    fn new(reply: fn() -> Result<Feed, Error>) -> Scripted {
+        Scripted {
+            reply,
+            calls: Cell::new(0),
+        }
+    }
+}
+
+impl Fetcher for Scripted {
+    fn fetch(&self, _location: &str) -> Result<Feed, Error> {
+        self.calls.set(self.calls.get() + 1);
+        (self.reply)()
+    }
+}
+
+fn fresh() -> Result<Feed, Error> {
+    Ok(Feed::new(String::from(FORECAST)))
+}
+
+fn server_error() -> Result<Feed, Error> {
+    Err(Error::ServerError {
+        url: Url::parse("http://127.0.0.1/").unwrap(),
+        status: 503,
+    })
+}
+
+fn rate_limited() -> Result<Feed, Error> {
+    Err(Error::RateLimited {
+        url: Url::parse("http://127.0.0.1/").unwrap(),
+        retry_after: None,
+    })
+}
+
+fn not_found() -> Result<Feed, Error> {
+    Err(Error::NotFound {
+        location: String::from(LONDON),
+    })
+}
+
+fn invalid_location() -> Result<Feed, Error> {
+    Err(Error::InvalidLocation(String::from("nowhere")))
+}
+
+/// A cache in its own directory holding London's forecast, fetched `age`
+/// ago.
+fn cache_with_entry(test: &str, age: Duration) -> Cache {
+    let cache = Cache::new(temp_dir(test));
+    let mut feed = Feed::new(String::from(FORECAST));
+    feed.set_fetched(Utc::now() - chrono::Duration::from_std(age).unwrap());
+    feed.set_etag(Some(String::from("\"v1\"")));
+    cache.store(LONDON, &feed).unwrap();
+    cache
+}
+
+#[test]
+fn serves_fresh_entries_without_fetching() {
+    let cache = cache_with_entry("fresh", Duration::from_secs(60));
+    let inner = Scripted::new(server_error);
+    let fetcher = CachingFetcher::new(&inner, cache, HOUR);
+    let feed = fetcher.fetch(LONDON).unwrap();
+    assert_eq!(inner.calls.get(), 0);
+    assert!(!feed.is_stale());
+    assert_eq!(feed.etag(), Some("\"v1\""));
+}
+
+#[test]
+fn fetches_again_once_the_ttl_has_passed() {
+    let cache = cache_with_entry("expired", 2 * HOUR);
+    let inner = Scripted::new(fresh);
+    let fetcher = CachingFetcher::new(&inner, cache, HOUR);
+    let feed = fetcher.fetch(LONDON).unwrap();
+    assert_eq!(inner.calls.get(), 1);
+    assert!(!feed.is_stale());
+    let entry = fetcher.cache().load(LONDON).unwrap().unwrap();
+    assert!(entry.age() < chrono::Duration::minutes(1));
+    assert_eq!(
+        entry.pub_date().map(|date| date.to_rfc2822()),
+        Some(String::from("Sun, 18 Oct 2026 05:00:00 +0000"))
+    );
+}
+
+#[test]
+fn falls_back_to_a_stale_copy_while_the_server_is_failing() {
+    for (test, reply) in [
+        ("server-error", server_error as fn() -> _),
+        ("rate-limited", rate_limited),
+    ] {
+        let cache = cache_with_entry(test, 2 * HOUR);
+        let fetcher = CachingFetcher::new(Scripted::new(reply), cache, HOUR);
+        let feed = fetcher.fetch(LONDON).unwrap();
+        assert!(feed.is_stale(), "{}", test);
+        assert_eq!(feed.body(), FORECAST);
+    }
+}
+
+#[test]
+fn reports_permanent_failures_despite_a_cached_copy() {
+    let cache = cache_with_entry("not-found", 2 * HOUR);
+    let fetcher = CachingFetcher::new(Scripted::new(not_found), cache, HOUR);
+    assert!(matches!(fetcher.fetch(LONDON), Err(Error::NotFound { .. })));
+
+    let cache = cache_with_entry("invalid-location", 2 * HOUR);
+    let fetcher = CachingFetcher::new(Scripted::new(invalid_location), cache, HOUR);
+    assert!(matches!(
+        fetcher.fetch(LONDON),
+        Err(Error::InvalidLocation(_))
+    ));
+}
+
+#[test]
+fn fails_without_a_cached_copy() {
+    let cache = Cache::new(temp_dir("empty"));
+    let fetcher = CachingFetcher::new(Scripted::new(server_error), cache, HOUR);
+    assert!(matches!(
+        fetcher.fetch(LONDON),
+        Err(Error::ServerError { .. })
+    ));
+}
+
+#[test]
+fn only_caches_feeds_which_parse() {
+    let cache = Cache::new(temp_dir("garbage"));
+    let inner = Scripted::new(|| Ok(Feed::new(String::from("<html>"))));
+    let fetcher = CachingFetcher::new(&inner, cache, HOUR);
+    assert!(fetcher.fetch(LONDON).unwrap().channel().is_none());
+    assert!(fetcher.cache().load(LONDON).unwrap().is_none());
+}
+
+#[test]
+fn hands_on_the_feed_it_parsed() {
+    let cache = Cache::new(temp_dir("parsed"));
+    let fetcher = CachingFetcher::new(Scripted::new(fresh), cache, HOUR);
+    let feed = fetcher.fetch(LONDON).unwrap();
+    assert_eq!(feed.channel().unwrap().get_items().len(), 3);
+}
+
+#[test]
+fn stores_lists_and_removes_entries() {
+    let cache = Cache::new(temp_dir("entries"));
+    let mut feed = Feed::new(String::from(FORECAST));
+    feed.set_last_modified(Some(String::from("Sun, 18 Oct 2026 05:00:00 GMT")));
+    cache.store(LONDON, &feed).unwrap();
+    cache.store("fr/SW1A", &feed).unwrap();
+
+    let entry = cache.load("fr/SW1A").unwrap().unwrap();
+    assert_eq!(entry.body(), FORECAST);
+    assert_eq!(entry.last_modified(), Some("Sun, 18 Oct 2026 05:00:00 GMT"));
+    let mut locations: Vec<String> = cache
+        .list()
+        .unwrap()
+        .iter()
+        .map(|entry| String::from(entry.location()))
+        .collect();
+    locations.sort();
+    assert_eq!(locations, [LONDON, "fr/SW1A"]);
+
+    assert!(cache.remove(LONDON).unwrap());
+    assert!(!cache.remove(LONDON).unwrap());
+    assert_eq!(cache.clear().unwrap(), 1);
+    assert!(cache.list().unwrap().is_empty());
+}
+
+#[test]
+fn treats_entries_from_the_future_as_stale() {
+    let cache = Cache::new(temp_dir("future"));
+    let mut feed = Feed::new(String::from(FORECAST));
+    feed.set_fetched(Utc::now() + chrono::Duration::days(1));
+    cache.store(LONDON, &feed).unwrap();
+    let inner = Scripted::new(fresh);
+    let fetcher = CachingFetcher::new(&inner, cache, HOUR);
+    assert!(!fetcher.fetch(LONDON).unwrap().is_stale());
+    assert_eq!(inner.calls.get(), 1);
+}
+
+#[test]
+fn lists_around_files_it_did_not_write() {
+    let dir = temp_dir("foreign");
+    let cache = Cache::new(&dir);
+    cache
+        .store(LONDON, &Feed::new(String::from(FORECAST)))
+        .unwrap();
+    for name in [
+        "caf\u{e9}.meta",
+        "%E9.meta",
+        "50%.meta",
+        "%+1.meta",
+        "a b.meta",
+    ] {
+        std::fs::write(dir.join(name), "fetched=2026-10-18T05:00:00+00:00\n").unwrap();
+    }
+    let entries = cache.list().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].location(), LONDON);
+}
+
+#[test]
+fn concurrent_stores_of_one_location_do_not_collide() {
+    let dir = temp_dir("concurrent");
+    std::thread::scope(|scope| {
+        for thread in 0..8 {
+            let cache = Cache::new(&dir);
+            scope.spawn(move || {
+                for write in 0..20 {
+                    let mut feed = Feed::new(String::from(FORECAST));
+                    feed.set_etag(Some(format!("\"{}-{}\"", thread, write)));
+                    cache.store(LONDON, &feed).unwrap();
+                }
+            });
+        }
+    });
+    let entry = Cache::new(&dir).load(LONDON).unwrap().unwrap();
+    assert_eq!(entry.body(), FORECAST);
+    assert!(entry.etag().unwrap().starts_with('"'));
+    let names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names.len(), 2, "{:?}", names);
+}
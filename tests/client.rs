@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use text_weather::error::Error;
+use text_weather::fetcher::{Feed, Fetcher};
+use text_weather::Client;
+
+/// Answers each location with its own name as the body, taking longer for
+/// earlier locations so they finish out of order, and keeps count of how
+/// many fetches were ever in flight at once.
+#[derive(Default)]
+struct Counting {
+    in_flight: AtomicUsize,
+    most_in_flight: AtomicUsize,
+    calls: AtomicUsize,
+}
+
+impl Fetcher for Counting {
+    fn fetch(&self, location: &str) -> Result<Feed, Error> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most_in_flight.fetch_max(now, Ordering::SeqCst);
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let index: u64 = location.parse().unwrap_or(0);
+        thread::sleep(Duration::from_millis(60 - 3 * index));
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        match location {
+            "bad" => Err(Error::InvalidLocation(String::from(location))),
+            _ => Ok(Feed::new(String::from(location))),
+        }
+    }
+}
+
+fn locations() -> Vec<String> {
+    (0..16).map(|index| index.to_string()).collect()
+}
+
+#[test]
+fn feeds_respects_the_concurrency_limit() {
+    let locations = locations();
+    let locations: Vec<&str> = locations.iter().map(String::as_str).collect();
+    for concurrency in [1, 3, 8] {
+        let mut client = Client::new(Counting::default());
+        client.set_concurrency(concurrency);
+        let feeds = client.feeds(&locations);
+        assert_eq!(feeds.len(), locations.len());
+        let fetcher = client.fetcher();
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), locations.len());
+        let most = fetcher.most_in_flight.load(Ordering::SeqCst);
+        assert!(
+            most <= concurrency,
+            "{} in flight with a limit of {}",
+            most,
+            concurrency
+        );
+        // With fetches this slow every worker gets a turn.
+        assert_eq!(most, concurrency);
+    }
+}
+
+#[test]
+fn feeds_come_back_in_the_order_asked_for() {
+    let mut locations = locations();
+    locations[5] = String::from("bad");
+    let locations: Vec<&str> = locations.iter().map(String::as_str).collect();
+    let mut client = Client::new(Counting::default());
+    client.set_concurrency(4);
+    let feeds = client.feeds(&locations);
+    for (location, feed) in locations.iter().zip(&feeds) {
+        match (location, feed) {
+            (&"bad", Err(Error::InvalidLocation(name))) => assert_eq!(name, "bad"),
+            (_, Ok(feed)) => assert_eq!(feed.body(), *location),
+            _ => panic!("{}: unexpected {:?}", location, feed),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! What the integration tests share: fixture feeds, scratch directories and
+//! a throwaway HTTP server. Each connection to the server gets the next
+//! scripted reply, the last one repeating once the script runs out, and
+//! every request head is kept for the test to inspect.
+
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use text_weather::fetcher::HttpOptions;
+use url::Url;
+
+pub const FORECAST: &str = include_str!("../fixtures/forecast.xml");
+pub const OBSERVATION: &str = include_str!("../fixtures/observation.xml");
+
+/// A London location id.
+pub const LONDON: &str = "2643743";
+
+#[derive(Clone)]
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Duration,
+}
+
+impl Reply {
+    /// 200 OK with an RSS body.
+    pub fn feed(body: &str) -> Reply {
+        Reply::status(200)
+            .header("Content-Type", "application/rss+xml")
+            .body(body)
+    }
+    pub fn status(status: u16) -> Reply {
+        Reply {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+            delay: Duration::from_secs(0),
+        }
+    }
+    pub fn header(mut self, name: &str, value: &str) -> Reply {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+    pub fn body(mut self, body: &str) -> Reply {
+        self.body = String::from(body);
+        self
+    }
+    /// Waits this long before answering, to trip request timeouts.
+    pub fn delay(mut self, delay: Duration) -> Reply {
+        self.delay = delay;
+        self
+    }
+}
+
+pub struct Server {
+    url: Url,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    pub fn start(replies: Vec<Reply>) -> Server {
+        assert!(!replies.is_empty(), "the server needs something to say");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        thread::spawn(move || {
+            for (number, stream) in listener.incoming().enumerate() {
+                let reply = replies[number.min(replies.len() - 1)].clone();
+                let seen = Arc::clone(&seen);
+                // Answer on a thread of its own so a delayed reply does not
+                // hold up the retry behind it.
+                thread::spawn(move || {
+                    if let Ok(stream) = stream {
+                        answer(stream, &reply, &seen);
+                    }
+                });
+            }
+        });
+        Server { url, requests }
+    }
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+    /// Options pointing at this server with quick retries and timeouts.
+    pub fn options(&self) -> HttpOptions {
+        let mut options = HttpOptions::default();
+        options.set_base_url(self.url.clone());
+        options.set_proxy(None);
+        options.set_timeout(Duration::from_millis(500));
+        options.set_initial_backoff(Duration::from_millis(10));
+        options.set_max_backoff(Duration::from_millis(50));
+        options
+    }
+    /// The head of every request received so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn answer(mut stream: TcpStream, reply: &Reply, seen: &Mutex<Vec<String>>) {
+    let mut head = Vec::new();
+    let mut byte = [0; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut byte) {
+            Ok(1) => head.push(byte[0]),
+            _ => return,
+        }
+    }
+    seen.lock()
+        .unwrap()
+        .push(String::from_utf8_lossy(&head).into_owned());
+    thread::sleep(reply.delay);
+    let mut response = format!(
+        "HTTP/1.1 {} Scripted\r\nContent-Length: {}\r\nConnection: close\r\n",
+        reply.status,
+        reply.body.len()
+    );
+    for (name, value) in &reply.headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(&reply.body);
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// An empty directory of its own for `test`, under the system temporary
+/// directory.
+pub fn temp_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("text_weather-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use text_weather::config::Config;
+use text_weather::language::Language;
+
+fn config(toml: &str) -> Config {
+    toml::from_str(toml).unwrap()
+}
+
+/// The settings `config` has problems with.
+fn problem_keys(config: &Config) -> Vec<String> {
+    config
+        .problems()
+        .into_iter()
+        .map(|(setting, _)| setting)
+        .collect()
+}
+
+#[test]
+fn applies_the_http_settings() {
+    let config = config(
+        r#"
+        [http]
+        base_url = "http://127.0.0.1:8765/bbc/"
+        language = "cy"
+        user_agent = "tests/1.0"
+        proxy = "http://proxy.example:3128"
+        connect_timeout = 3
+        timeout = 20
+        retries = 1
+        "#,
+    );
+    assert!(config.problems().is_empty());
+    let options = config.http_options().unwrap();
+    assert_eq!(options.base_url().as_str(), "http://127.0.0.1:8765/bbc/");
+    assert_eq!(options.language(), Language::Welsh);
+    assert_eq!(options.user_agent(), "tests/1.0");
+    assert_eq!(options.proxy(), Some("http://proxy.example:3128"));
+    assert_eq!(options.connect_timeout(), Duration::from_secs(3));
+    assert_eq!(options.timeout(), Duration::from_secs(20));
+    assert_eq!(options.retries(), 1);
+}
+
+#[test]
+fn names_the_http_setting_that_failed() {
+    let cases = [
+        ("base_url = \"not a url\"", "http.base_url"),
+        ("base_url = \"mailto:someone@example.com\"", "http.base_url"),
+        ("user_agent = \"two\\nlines\"", "http.user_agent"),
+        ("proxy = \"not a url\"", "http.proxy"),
+        (
+            "ca_certificates = [\"/nonexistent/ca.pem\"]",
+            "http.ca_certificates",
+        ),
+        ("connect_timeout = 0", "http.connect_timeout"),
+        ("timeout = 0", "http.timeout"),
+    ];
+    for (setting, key) in cases.iter() {
+        let config = config(&format!("[http]\n{}\n", setting));
+        assert_eq!(problem_keys(&config), [*key], "{}", setting);
+        assert!(config.http_options().is_err(), "{}", setting);
+    }
+}
+
+#[test]
+fn reports_every_problem_at_once() {
+    let config = config(
+        r#"
+        format = "{nonsense}"
+
+        [locations]
+        home = "Xyzzyville"
+
+        [http]
+        proxy = "not a url"
+        timeout = 0
+        "#,
+    );
+    assert_eq!(
+        problem_keys(&config),
+        ["locations.home", "format", "http.proxy", "http.timeout"]
+    );
+}
@@ -0,0 +1,56 @@
+use chrono::{NaiveDate, Weekday};
+use text_weather::dayoption::Day;
+
+/// A Sunday.
+fn issued() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+}
+
+#[test]
+fn relative_days_count_from_the_issue_date() {
+    assert_eq!(Day::Today.index(issued(), 3), Some(0));
+    assert_eq!(Day::Tomorrow.index(issued(), 3), Some(1));
+    assert_eq!(Day::Overmorrow.index(issued(), 3), Some(2));
+}
+
+#[test]
+fn weekdays_mean_their_next_occurrence() {
+    assert_eq!(Day::Weekday(Weekday::Sun).index(issued(), 3), Some(0));
+    assert_eq!(Day::Weekday(Weekday::Mon).index(issued(), 3), Some(1));
+    assert_eq!(Day::Weekday(Weekday::Tue).index(issued(), 3), Some(2));
+    assert_eq!(Day::Weekday(Weekday::Wed).index(issued(), 3), None);
+    assert_eq!(Day::Weekday(Weekday::Sat).index(issued(), 3), None);
+}
+
+#[test]
+fn dates_must_fall_within_the_forecast() {
+    let date = |day| Day::Date(NaiveDate::from_ymd_opt(2026, 10, day).unwrap());
+    assert_eq!(date(17).index(issued(), 3), None);
+    assert_eq!(date(18).index(issued(), 3), Some(0));
+    assert_eq!(date(20).index(issued(), 3), Some(2));
+    assert_eq!(date(21).index(issued(), 3), None);
+}
+
+#[test]
+fn parses_names_weekdays_and_dates() {
+    assert_eq!("Tomorrow".parse(), Ok(Day::Tomorrow));
+    assert_eq!(" overmorrow ".parse(), Ok(Day::Overmorrow));
+    assert_eq!("fri".parse(), Ok(Day::Weekday(Weekday::Fri)));
+    assert_eq!("Friday".parse(), Ok(Day::Weekday(Weekday::Fri)));
+    assert_eq!(
+        "2026-10-20".parse(),
+        Ok(Day::Date(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()))
+    );
+    assert!("someday".parse::<Day>().is_err());
+    assert!("2026-13-01".parse::<Day>().is_err());
+}
+
+#[test]
+fn displays_days_by_name() {
+    assert_eq!(Day::Overmorrow.to_string(), "Overmorrow");
+    assert_eq!(Day::Weekday(Weekday::Wed).to_string(), "Wednesday");
+    assert_eq!(
+        Day::Date(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()).to_string(),
+        "Tuesday 2026-10-20"
+    );
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{FORECAST, LONDON};
+use text_weather::fetcher::Feed;
+use text_weather::render::{RenderOptions, Renderers};
+use text_weather::LocationForecast;
+
+/// The fixture with markup in its place name, description and conditions.
+fn forecast() -> LocationForecast {
+    let body = FORECAST
+        .replace("London, GB", "London &lt;b&gt; *GB* #1")
+        .replace("3-day forecast", "`3-day` forecast [BBC]")
+        .replace("Sunny Intervals", "+Sunny_Intervals_ !")
+        .replace("Thundery Showers", "-Thundery - Showers +")
+        .replace("Light Rain", "Light Rain | &amp; \"Drizzle\"");
+    LocationForecast::new(LONDON, Feed::new(body), None).unwrap()
+}
+
+fn rendered(name: &str) -> String {
+    let mut out = Vec::new();
+    Renderers::default()
+        .render(name, &mut out, &[forecast()], &RenderOptions::default())
+        .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn markdown_escapes_its_special_characters() {
+    let markdown = rendered("markdown");
+    assert!(
+        markdown.contains("## London \\<b\\> \\*GB\\* \\#1\n"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("\\`3-day\\` forecast \\[BBC\\]"),
+        "{}",
+        markdown
+    );
+    // A pipe would otherwise end the table cell early.
+    let summary = markdown
+        .lines()
+        .find(|line| line.starts_with("| **Summary** |"))
+        .unwrap();
+    assert_eq!(
+        summary,
+        "| **Summary** | Light Rain \\| & \"Drizzle\" | \\+Sunny\\_Intervals\\_ \\! | \\-Thundery - Showers + |"
+    );
+    assert_eq!(summary.matches(" | ").count(), 3);
+    assert!(markdown.contains("[BBC Weather](<https://www.bbc.co.uk/weather/2643743>)"));
+}
+
+#[test]
+fn html_escapes_text_and_attributes() {
+    let html = rendered("html");
+    assert!(
+        html.contains("<h2>London &lt;b&gt; *GB* #1</h2>"),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(">Light Rain | &amp; &quot;Drizzle&quot;</td>"),
+        "{}",
+        html
+    );
+    assert!(html.contains(r#"<a href="https://www.bbc.co.uk/weather/2643743">"#));
+    assert!(!html.contains("<b>"), "{}", html);
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n"));
+    assert!(html.trim_end().ends_with("</html>"));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{temp_dir, FORECAST, LONDON};
+use text_weather::dayoption::Day;
+use text_weather::error::{Error, ParseError};
+use text_weather::fetcher::{Feed, Fetcher, FileFetcher, HttpFetcher, HttpOptions};
+use text_weather::parser::parse_document;
+use text_weather::LocationForecast;
+use url::Url;
+
+/// `error` followed by each of its sources in turn.
+fn chain<'a>(
+    error: &'a (dyn std::error::Error + 'static),
+) -> Vec<&'a (dyn std::error::Error + 'static)> {
+    let mut chain = vec![error];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        chain.push(cause);
+        source = cause.source();
+    }
+    chain
+}
+
+#[test]
+fn http_errors_name_the_url_and_keep_the_cause() {
+    // A port nothing is listening on.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let base = Url::parse(&format!("http://127.0.0.1:{}/", port)).unwrap();
+    let mut options = HttpOptions::default();
+    options.set_base_url(base.clone());
+    options.set_proxy(None);
+    options.set_retries(0);
+    let e = HttpFetcher::new(options)
+        .unwrap()
+        .fetch(LONDON)
+        .unwrap_err();
+    match &e {
+        Error::Reqwest { url, .. } => assert!(url.as_str().starts_with(base.as_str()), "{}", url),
+        other => panic!("expected Reqwest, got {:?}", other),
+    }
+    let chain = chain(&e);
+    assert!(chain[0]
+        .to_string()
+        .starts_with("Failed to fetch http://127.0.0.1:"));
+    assert!(chain[1].is::<reqwest::Error>(), "{:?}", chain);
+    let refused = chain
+        .iter()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|e| e.kind() == std::io::ErrorKind::ConnectionRefused);
+    assert!(refused, "{:?}", chain);
+}
+
+#[test]
+fn io_errors_name_the_path_and_keep_the_cause() {
+    let path = temp_dir("io_errors_name_the_path").join("missing.xml");
+    let e = FileFetcher::new(&path).fetch(LONDON).unwrap_err();
+    let chain = chain(&e);
+    assert_eq!(chain.len(), 2, "{:?}", chain);
+    assert_eq!(
+        chain[0].to_string(),
+        format!("Could not read {}", path.display())
+    );
+    let io = chain[1].downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn parse_errors_keep_where_and_why() {
+    let body = "<rss>\n<channel>\n  <title>London</titel>\n</channel>\n</rss>\n";
+    let e = parse_document(body).unwrap_err();
+    let chain = chain(&e);
+    assert_eq!(chain.len(), 3, "{:?}", chain);
+    assert_eq!(chain[0].to_string(), "Could not parse the feed");
+    let parse = chain[1].downcast_ref::<ParseError>().unwrap();
+    assert_eq!(parse.line(), 3);
+    assert_eq!(parse.path(), "/rss/channel/title");
+    assert!(
+        parse.to_string().starts_with("Invalid XML at line 3"),
+        "{}",
+        parse
+    );
+    assert!(chain[2].is::<quick_xml::Error>(), "{:?}", chain);
+}
+
+#[test]
+fn missing_elements_end_the_chain() {
+    let body = FORECAST
+        .replace("<guid", "<guide")
+        .replace("</guid>", "</guide>");
+    let e = parse_document(&body).unwrap_err();
+    let chain = chain(&e);
+    assert_eq!(chain.len(), 2, "{:?}", chain);
+    assert!(
+        chain[1]
+            .to_string()
+            .starts_with("Missing <guid> in /rss/channel/item"),
+        "{}",
+        chain[1]
+    );
+}
+
+#[test]
+fn days_not_forecast_give_the_range_the_feed_covers() {
+    let day: Day = "2026-10-21".parse().unwrap();
+    let e = LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), Some(day))
+        .err()
+        .unwrap();
+    assert_eq!(
+        e.to_string(),
+        "There is no forecast for Wednesday 2026-10-21, the forecast issued on 2026-10-18 covers \
+         2026-10-18 to 2026-10-20"
+    );
+
+    // A feed with only two days covers only two.
+    let last = FORECAST.rfind("<item>").unwrap();
+    let end = FORECAST.rfind("</item>").unwrap() + "</item>".len();
+    let body = format!("{}{}", &FORECAST[..last], &FORECAST[end..]);
+    let day: Day = "2026-10-20".parse().unwrap();
+    match LocationForecast::new(LONDON, Feed::new(body), Some(day)) {
+        Err(e @ Error::DayNotForecast { days: 2, .. }) => {
+            assert!(
+                e.to_string().ends_with("covers 2026-10-18 to 2026-10-19"),
+                "{}",
+                e
+            )
+        }
+        other => panic!("expected DayNotForecast, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{FORECAST, LONDON, OBSERVATION};
+use text_weather::error::Error;
+use text_weather::fetcher::{Fetcher, FileFetcher, HttpFetcher, HttpOptions, MemoryFetcher};
+use text_weather::language::Language;
+use text_weather::{Client, FeedKind};
+use url::Url;
+
+#[test]
+fn memory_fetcher_serves_what_was_inserted() {
+    let mut fetcher = MemoryFetcher::new();
+    fetcher.insert(LONDON, FORECAST);
+    assert_eq!(fetcher.fetch(LONDON).unwrap().body(), FORECAST);
+    match fetcher.fetch("2653822") {
+        Err(Error::NotFound { location }) => assert_eq!(location, "2653822"),
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn memory_fetcher_falls_back_for_other_locations() {
+    let mut fetcher = MemoryFetcher::with_fallback(FORECAST);
+    fetcher.insert(LONDON, OBSERVATION);
+    assert_eq!(fetcher.fetch(LONDON).unwrap().body(), OBSERVATION);
+    assert_eq!(fetcher.fetch("2653822").unwrap().body(), FORECAST);
+}
+
+#[test]
+fn file_fetcher_reads_the_same_file_for_any_location() {
+    let fetcher = FileFetcher::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/forecast.xml"
+    ));
+    assert_eq!(fetcher.fetch(LONDON).unwrap().body(), FORECAST);
+    assert!(FileFetcher::new("/nonexistent/feed.xml")
+        .fetch(LONDON)
+        .is_err());
+}
+
+#[test]
+fn run_parses_fixture_forecasts() {
+    let client = Client::new(MemoryFetcher::with_fallback(FORECAST));
+    let forecasts = text_weather::run(&client, &[LONDON, "SW1A"], None)
+        .into_result()
+        .unwrap();
+    assert_eq!(forecasts.len(), 2);
+    assert_eq!(forecasts[1].location(), "SW1A");
+    let summaries: Vec<&str> = forecasts[0]
+        .days()
+        .map(|(_, daily)| daily.summary().summary())
+        .collect();
+    assert_eq!(
+        summaries,
+        ["Light Rain", "Sunny Intervals", "Thundery Showers"]
+    );
+}
+
+#[test]
+fn run_keeps_going_past_failed_locations() {
+    let mut fetcher = MemoryFetcher::new();
+    fetcher.insert(LONDON, FORECAST);
+    fetcher.insert("SW1A", "not a feed");
+    let client = Client::new(fetcher);
+    let outcome = text_weather::run(&client, &[LONDON, "SW1A", "2653822"], None);
+    assert_eq!(outcome.successes().len(), 1);
+    let failed: Vec<&str> = outcome
+        .failures()
+        .iter()
+        .map(|(location, _)| location.as_str())
+        .collect();
+    assert_eq!(failed, ["SW1A", "2653822"]);
+    match outcome.into_result() {
+        Err(Error::Locations {
+            attempted,
+            failures,
+        }) => assert_eq!((attempted, failures.len()), (3, 2)),
+        other => panic!("expected Locations, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn observe_parses_fixture_observations() {
+    let client = Client::new(MemoryFetcher::with_fallback(OBSERVATION));
+    let observations = text_weather::observe(&client, &[LONDON])
+        .into_result()
+        .unwrap();
+    assert_eq!(observations.len(), 1);
+    assert_eq!(observations[0].location(), LONDON);
+}
+
+fn cache_key(options: HttpOptions, location: &str) -> String {
+    HttpFetcher::new(options).unwrap().cache_key(location)
+}
+
+#[test]
+fn http_cache_keys_tell_feeds_apart() {
+    assert_eq!(cache_key(HttpOptions::default(), " sw1a "), "SW1A");
+
+    let mut options = HttpOptions::default();
+    options.set_language(Language::Welsh);
+    options.set_feed_kind(FeedKind::Observation);
+    assert_eq!(cache_key(options, LONDON), "cy/observation/2643743");
+
+    let mut options = HttpOptions::default();
+    options.set_base_url(Url::parse("http://127.0.0.1:8765").unwrap());
+    assert_eq!(cache_key(options, LONDON), "127.0.0.1:8765/2643743");
+
+    let mut options = HttpOptions::default();
+    options.set_base_url(Url::parse("https://mirror.example/bbc/").unwrap());
+    options.set_language(Language::Gaelic);
+    assert_eq!(cache_key(options, LONDON), "mirror.example/bbc/gd/2643743");
+}
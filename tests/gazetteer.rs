@@ -0,0 +1,160 @@
+use text_weather::error::Error;
+use text_weather::gazetteer::{self, MatchKind};
+
+fn resolve_id(query: &str) -> u32 {
+    gazetteer::resolve(query)
+        .unwrap_or_else(|e| panic!("{:?} did not resolve: {}", query, e))
+        .id()
+}
+
+#[test]
+fn search_ranks_better_matches_then_bigger_places_first() {
+    let matches = gazetteer::search("new");
+    assert!(!matches.is_empty());
+    assert!(matches.windows(2).all(|pair| {
+        let (a, b) = (&pair[0], &pair[1]);
+        a.kind() < b.kind()
+            || (a.kind() == b.kind() && a.place().population() >= b.place().population())
+    }));
+    assert_eq!(matches[0].place().name(), "New York");
+    assert_eq!(matches[0].kind(), MatchKind::Words);
+}
+
+#[test]
+fn search_ignores_case_and_punctuation() {
+    let matches = gazetteer::search("stoke on TRENT");
+    assert_eq!(matches[0].place().name(), "Stoke-on-Trent");
+    assert_eq!(matches[0].kind(), MatchKind::Exact);
+    assert!(gazetteer::search(" , GB").is_empty());
+}
+
+#[test]
+fn search_filters_by_country_or_region() {
+    let matches = gazetteer::search("Portland, maine");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].place().admin(), "Maine");
+}
+
+#[test]
+fn resolves_unique_and_qualified_names() {
+    assert_eq!(resolve_id("Edinburgh"), 2650225);
+    assert_eq!(resolve_id("London, GB"), 2643743);
+    assert_eq!(resolve_id("Perth, AU"), 2063523);
+    assert_eq!(resolve_id("Perth, Scotland"), 2640351);
+    assert_eq!(resolve_id("Portland, Oregon"), 5746545);
+}
+
+#[test]
+fn resolves_small_spelling_mistakes() {
+    assert_eq!(resolve_id("Edinbrugh"), 2650225);
+    assert_eq!(resolve_id("Plymoth"), 2640194);
+}
+
+#[test]
+fn unknown_places_are_reported() {
+    match gazetteer::resolve("Xyzzyville") {
+        Err(Error::UnknownPlace(query)) => assert_eq!(query, "Xyzzyville"),
+        other => panic!("expected UnknownPlace, got {:?}", other),
+    }
+}
+
+#[test]
+fn ambiguous_places_suggest_how_to_choose() {
+    let e = gazetteer::resolve("Portland").unwrap_err();
+    match &e {
+        Error::AmbiguousPlace { candidates, .. } => assert_eq!(candidates.len(), 2),
+        other => panic!("expected AmbiguousPlace, got {:?}", other),
+    }
+    // Both are in the US, so the region tells them apart.
+    assert!(
+        e.to_string().contains("such as \"Portland, Oregon\""),
+        "{}",
+        e
+    );
+
+    let e = gazetteer::resolve("Perth").unwrap_err();
+    assert!(e.to_string().contains("such as \"Perth, AU\""), "{}", e);
+
+    // An exact name and the leading words of another are equally good.
+    let e = gazetteer::resolve("Newcastle").unwrap_err();
+    assert!(e.to_string().contains("Newcastle upon Tyne"), "{}", e);
+}
+
+#[test]
+fn ambiguous_place_messages_survive_any_number_of_candidates() {
+    let london = gazetteer::resolve("London, GB").unwrap().clone();
+    for candidates in [Vec::new(), vec![london]] {
+        let message = Error::AmbiguousPlace {
+            query: String::from("London"),
+            candidates,
+        }
+        .to_string();
+        assert!(message.starts_with("\"London\" matches"), "{}", message);
+    }
+}
+
+/// Great circle distance in kilometres, worked out independently of the
+/// gazetteer's own.
+fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lon1, lat2, lon2) = (
+        lat1.to_radians(),
+        lon1.to_radians(),
+        lat2.to_radians(),
+        lon2.to_radians(),
+    );
+    let cosine = lat1.sin() * lat2.sin() + lat1.cos() * lat2.cos() * (lon2 - lon1).cos();
+    6371.0088 * cosine.clamp(-1.0, 1.0).acos()
+}
+
+#[test]
+fn nearest_agrees_with_a_brute_force_search() {
+    let mut latitude = -90.0;
+    while latitude <= 90.0 {
+        let mut longitude = -180.0;
+        while longitude <= 180.0 {
+            let found = gazetteer::nearest(latitude, longitude).unwrap();
+            let closest = gazetteer::places()
+                .iter()
+                .map(|place| distance_km(latitude, longitude, place.latitude(), place.longitude()))
+                .fold(f64::INFINITY, f64::min);
+            assert!(
+                (found.distance_km() - closest).abs() < 0.01,
+                "{}, {}: found {} at {} km but something is {} km away",
+                latitude,
+                longitude,
+                found.place(),
+                found.distance_km(),
+                closest
+            );
+            longitude += 7.5;
+        }
+        latitude += 7.5;
+    }
+}
+
+#[test]
+fn nearest_finds_places_on_top_of_the_coordinates() {
+    let nearest = gazetteer::nearest(51.50853, -0.12574).unwrap();
+    assert_eq!(nearest.place().id(), 2643743);
+    assert!(nearest.distance_km() < 0.001);
+    // Auckland is closest to 179.9 degrees west, across the antimeridian.
+    let nearest = gazetteer::nearest(-36.85, -179.9).unwrap();
+    assert_eq!(nearest.place().name(), "Auckland");
+}
+
+#[test]
+fn nearest_reports_far_away_places_honestly() {
+    // Point Nemo, as far from land as the ocean gets.
+    let (latitude, longitude) = (-48.8767, -123.3933);
+    let nearest = gazetteer::nearest(latitude, longitude).unwrap();
+    let place = nearest.place();
+    let distance = distance_km(latitude, longitude, place.latitude(), place.longitude());
+    assert!((nearest.distance_km() - distance).abs() < 0.01);
+    assert!(nearest.distance_km() > 2_500.0, "{}", nearest.distance_km());
+    assert!(!nearest.is_nearby());
+
+    // A few kilometres out of Bristol is still Bristol.
+    let nearest = gazetteer::nearest(51.50, -2.55).unwrap();
+    assert_eq!(nearest.place().name(), "Bristol");
+    assert!(nearest.is_nearby(), "{}", nearest.distance_km());
+}
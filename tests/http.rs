@@ -0,0 +1,121 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{temp_dir, Reply, Server, FORECAST, LONDON};
+use text_weather::cache::{Cache, CachingFetcher};
+use text_weather::error::Error;
+use text_weather::fetcher::{Feed, Fetcher, HttpFetcher};
+
+/// Fetches London from a server giving `reply`, without retrying.
+fn fetch_once(reply: Reply) -> Result<String, Error> {
+    let server = Server::start(vec![reply]);
+    let mut options = server.options();
+    options.set_retries(0);
+    let fetcher = HttpFetcher::new(options).unwrap();
+    fetcher.fetch(LONDON).map(|feed| String::from(feed.body()))
+}
+
+#[test]
+fn accepts_an_rss_feed() {
+    assert_eq!(fetch_once(Reply::feed(FORECAST)).unwrap(), FORECAST);
+}
+
+#[test]
+fn accepts_a_feed_without_a_content_type() {
+    assert!(fetch_once(Reply::status(200).body(FORECAST)).is_ok());
+}
+
+#[test]
+fn not_found_names_the_location() {
+    match fetch_once(Reply::status(404)) {
+        Err(Error::NotFound { location }) => assert_eq!(location, LONDON),
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn too_many_requests_is_rate_limiting() {
+    match fetch_once(Reply::status(429).header("Retry-After", "7")) {
+        Err(Error::RateLimited { retry_after, .. }) => {
+            assert_eq!(retry_after.map(|d| d.as_secs()), Some(7))
+        }
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+}
+
+#[test]
+fn server_errors_keep_their_status() {
+    match fetch_once(Reply::status(503)) {
+        Err(Error::ServerError { status, .. }) => assert_eq!(status, 503),
+        other => panic!("expected ServerError, got {:?}", other),
+    }
+}
+
+#[test]
+fn other_failures_are_unexpected() {
+    match fetch_once(Reply::status(403)) {
+        Err(Error::UnexpectedStatus { status, .. }) => assert_eq!(status, 403),
+        other => panic!("expected UnexpectedStatus, got {:?}", other),
+    }
+}
+
+#[test]
+fn html_is_not_a_feed() {
+    let reply = Reply::status(200)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body("<html></html>");
+    match fetch_once(reply) {
+        Err(Error::UnexpectedContentType { content_type, .. }) => {
+            assert_eq!(content_type, "text/html; charset=utf-8")
+        }
+        other => panic!("expected UnexpectedContentType, got {:?}", other),
+    }
+}
+
+#[test]
+fn revalidation_sends_the_cached_validators() {
+    let server = Server::start(vec![Reply::status(304)]);
+    let fetcher = HttpFetcher::new(server.options()).unwrap();
+    let mut cached = Feed::new(String::from(FORECAST));
+    cached.set_etag(Some(String::from("\"v1\"")));
+    cached.set_last_modified(Some(String::from("Sun, 18 Oct 2026 05:00:00 GMT")));
+    let feed = fetcher.revalidate(LONDON, &cached).unwrap();
+    assert_eq!(feed.body(), FORECAST);
+    assert_eq!(feed.etag(), Some("\"v1\""));
+    let request = server.requests()[0].to_ascii_lowercase();
+    assert!(request.contains("if-none-match: \"v1\"\r\n"), "{}", request);
+    assert!(
+        request.contains("if-modified-since: sun, 18 oct 2026 05:00:00 gmt\r\n"),
+        "{}",
+        request
+    );
+}
+
+#[test]
+fn the_cache_reuses_its_body_when_not_modified() {
+    let server = Server::start(vec![
+        Reply::feed(FORECAST).header("ETag", "\"v1\""),
+        Reply::status(304).header("ETag", "\"v2\""),
+    ]);
+    let cache = Cache::new(temp_dir("not-modified"));
+    // With no time to live every fetch after the first revalidates.
+    let fetcher = CachingFetcher::new(
+        HttpFetcher::new(server.options()).unwrap(),
+        cache,
+        Duration::from_secs(0),
+    );
+    fetcher.fetch(LONDON).unwrap();
+    let feed = fetcher.fetch(LONDON).unwrap();
+    assert_eq!(feed.body(), FORECAST);
+    assert!(!feed.is_stale());
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1]
+        .to_ascii_lowercase()
+        .contains("if-none-match: \"v1\"\r\n"));
+    let key = fetcher.cache_key(LONDON);
+    let entry = fetcher.cache().load(&key).unwrap().unwrap();
+    assert_eq!(entry.etag(), Some("\"v2\""));
+    assert_eq!(entry.body(), FORECAST);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+use common::temp_dir;
+
+/// The first line of the ASCII picture of light rain in the fixture.
+const RAIN_ART: &str = "     .-.       Summary: Light Rain";
+
+/// What `text_weather forecast` prints for the fixture with `args`, piped
+/// rather than written to a terminal and with the config at `config`.
+fn forecast(config: &Path, args: &[&str]) -> String {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/forecast.xml");
+    let output = Command::new(env!("CARGO_BIN_EXE_text_weather"))
+        .arg("--config")
+        .arg(config)
+        .args(["forecast", "--input"])
+        .arg(fixture)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn config(test: &str, contents: &str) -> std::path::PathBuf {
+    let path = temp_dir(test).join("config.toml");
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn piped_output_has_no_icons_unless_asked() {
+    let config = config("icons_piped", "");
+    let plain = forecast(&config, &[]);
+    assert!(
+        plain.starts_with("Today\nSummary: Light Rain\n"),
+        "{}",
+        plain
+    );
+
+    let ascii = forecast(&config, &["--icons", "ascii"]);
+    assert!(ascii.contains(RAIN_ART), "{}", ascii);
+    let emoji = forecast(&config, &["--icons", "emoji"]);
+    assert!(
+        emoji.contains("🌧\u{fe0f}  Summary: Light Rain"),
+        "{}",
+        emoji
+    );
+}
+
+#[test]
+fn no_icons_overrides_the_config_file() {
+    let config = config("icons_config", "icons = \"ascii\"\n");
+    assert!(forecast(&config, &[]).contains(RAIN_ART));
+    let plain = forecast(&config, &["--no-icons"]);
+    assert!(
+        plain.starts_with("Today\nSummary: Light Rain\n"),
+        "{}",
+        plain
+    );
+    assert!(!plain.contains(".-."), "{}", plain);
+}
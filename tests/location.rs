@@ -0,0 +1,70 @@
+use text_weather::error::Error;
+use text_weather::fetcher::HttpOptions;
+use text_weather::language::Language;
+use text_weather::{normalise_location, FeedKind};
+use url::Url;
+
+#[test]
+fn accepts_geonames_ids() {
+    assert_eq!(normalise_location("2643743").unwrap(), "2643743");
+    assert_eq!(normalise_location(" 2643743\n").unwrap(), "2643743");
+    assert_eq!(normalise_location("1234567890").unwrap(), "1234567890");
+}
+
+#[test]
+fn accepts_postcode_districts_in_every_form() {
+    for (district, normalised) in [
+        ("m1", "M1"),
+        ("B33", "B33"),
+        ("w1a", "W1A"),
+        ("Cr2", "CR2"),
+        ("sw19", "SW19"),
+        (" SW1A ", "SW1A"),
+    ] {
+        assert_eq!(normalise_location(district).unwrap(), normalised);
+    }
+}
+
+#[test]
+fn rejects_anything_else() {
+    for location in [
+        "",
+        "   ",
+        "12345678901",
+        "2643743a",
+        "London",
+        "SW1A 1AA",
+        "ABC1",
+        "1A",
+        "SW1AB",
+        "../2643743",
+    ] {
+        match normalise_location(location) {
+            Err(Error::InvalidLocation(rejected)) => assert_eq!(rejected, location),
+            other => panic!("{:?} should be rejected, got {:?}", location, other),
+        }
+    }
+}
+
+#[test]
+fn feed_urls_are_built_from_the_normalised_location() {
+    let options = HttpOptions::default();
+    assert_eq!(
+        options.feed_url("sw1a").unwrap().as_str(),
+        "https://weather-broker-cdn.api.bbci.co.uk/en/forecast/rss/3day/SW1A"
+    );
+
+    let mut options = HttpOptions::default();
+    options.set_base_url(Url::parse("https://mirror.example/bbc/").unwrap());
+    options.set_language(Language::Welsh);
+    options.set_feed_kind(FeedKind::Observation);
+    assert_eq!(
+        options.feed_url("2643743").unwrap().as_str(),
+        "https://mirror.example/bbc/cy/observation/rss/2643743"
+    );
+
+    assert!(matches!(
+        options.feed_url("London"),
+        Err(Error::InvalidLocation(_))
+    ));
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{Reply, Server, FORECAST, LONDON};
+use text_weather::error::Error;
+use text_weather::nonblocking::Client;
+
+#[tokio::test]
+async fn fetches_a_forecast() {
+    let server = Server::start(vec![Reply::feed(FORECAST)]);
+    let client = Client::new(server.options()).unwrap();
+    let forecast = client.forecast(LONDON).await.unwrap();
+    assert_eq!((&forecast).into_iter().count(), 3);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(
+        requests[0].starts_with(&format!("GET /en/forecast/rss/3day/{} ", LONDON)),
+        "{}",
+        requests[0]
+    );
+}
+
+#[tokio::test]
+async fn retries_with_backoff() {
+    let server = Server::start(vec![
+        Reply::status(503),
+        Reply::status(503),
+        Reply::feed(FORECAST),
+    ]);
+    let mut options = server.options();
+    options.set_retries(2);
+    options.set_initial_backoff(Duration::from_millis(100));
+    options.set_max_backoff(Duration::from_secs(1));
+    let client = Client::new(options).unwrap();
+    let started = Instant::now();
+    assert_eq!(client.feed(LONDON).await.unwrap().body(), FORECAST);
+    assert_eq!(server.requests().len(), 3);
+    // At least half of each 100ms and 200ms delay, whatever the jitter.
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn gives_up_once_the_retries_run_out() {
+    let server = Server::start(vec![Reply::status(503)]);
+    let mut options = server.options();
+    options.set_retries(1);
+    let client = Client::new(options).unwrap();
+    match client.feed(LONDON).await {
+        Err(Error::ServerError { status, .. }) => assert_eq!(status, 503),
+        other => panic!("expected ServerError, got {:?}", other),
+    }
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn fetches_several_locations_at_once() {
+    let delay = Duration::from_millis(300);
+    let server = Server::start(vec![Reply::feed(FORECAST).delay(delay)]);
+    let client = Client::new(server.options()).unwrap();
+    let started = Instant::now();
+    let (london, paris, tokyo) = tokio::join!(
+        client.forecast(LONDON),
+        client.forecast("2988507"),
+        client.forecast("1850147"),
+    );
+    // One after the other they would take three delays.
+    assert!(started.elapsed() < delay * 2, "{:?}", started.elapsed());
+    for forecast in [london, paris, tokyo] {
+        assert_eq!((&forecast.unwrap()).into_iter().count(), 3);
+    }
+    let mut locations: Vec<String> = server
+        .requests()
+        .iter()
+        .map(|request| {
+            request
+                .split(' ')
+                .nth(1)
+                .unwrap()
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    locations.sort();
+    assert_eq!(locations, ["1850147", "2643743", "2988507"]);
+}
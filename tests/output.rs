@@ -0,0 +1,218 @@
+mod common;
+
+use common::{FORECAST, LONDON, OBSERVATION};
+use serde_json::Value;
+use text_weather::fetcher::Feed;
+use text_weather::output::{self, ObservationReport, OutputFormat, Report, SCHEMA_VERSION};
+use text_weather::{LocationForecast, LocationObservation};
+
+const ROW_COLUMNS: [&str; 25] = [
+    "schema_version",
+    "location_id",
+    "location_name",
+    "location_link",
+    "location_language",
+    "issued",
+    "fetched",
+    "stale",
+    "index",
+    "date",
+    "weekday",
+    "condition",
+    "temperature_max_c",
+    "temperature_max_f",
+    "temperature_min_c",
+    "temperature_min_f",
+    "wind_direction",
+    "wind_speed_mph",
+    "visibility",
+    "pressure_mb",
+    "humidity_percent",
+    "uv_risk",
+    "pollution",
+    "sunrise",
+    "sunset",
+];
+
+const OBSERVATION_COLUMNS: [&str; 18] = [
+    "schema_version",
+    "location_id",
+    "location_name",
+    "location_link",
+    "location_language",
+    "issued",
+    "fetched",
+    "stale",
+    "observed",
+    "condition",
+    "temperature_c",
+    "temperature_f",
+    "wind_direction",
+    "wind_speed_mph",
+    "humidity_percent",
+    "pressure_mb",
+    "pressure",
+    "visibility",
+];
+
+fn reports() -> Vec<Report> {
+    let forecast = LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), None).unwrap();
+    vec![Report::new(&forecast)]
+}
+
+fn observation_reports() -> Vec<ObservationReport> {
+    let observed = LocationObservation::new(LONDON, Feed::new(String::from(OBSERVATION))).unwrap();
+    vec![ObservationReport::new(&observed)]
+}
+
+fn written<F: FnOnce(&mut Vec<u8>) -> std::io::Result<()>>(write: F) -> String {
+    let mut out = Vec::new();
+    write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// The header and rows of a CSV document.
+fn csv_records(csv: &str) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let header = reader.headers().unwrap().iter().map(String::from).collect();
+    let rows = reader
+        .records()
+        .map(|record| record.unwrap().iter().map(String::from).collect())
+        .collect();
+    (header, rows)
+}
+
+#[test]
+fn json_follows_the_schema() {
+    let json = written(|out| output::write_json(&reports(), out));
+    let document: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(document["schema_version"], SCHEMA_VERSION);
+    let forecast = &document["forecasts"][0];
+    assert_eq!(
+        forecast["location"],
+        serde_json::json!({
+            "id": "2643743",
+            "name": "London, GB",
+            "link": "https://www.bbc.co.uk/weather/2643743",
+            "language": "en",
+        })
+    );
+    assert_eq!(forecast["issued"], "2026-10-18T05:00:00+00:00");
+    assert_eq!(forecast["stale"], false);
+    let days = forecast["days"].as_array().unwrap();
+    assert_eq!(days.len(), 3);
+    assert_eq!(
+        days[1],
+        serde_json::json!({
+            "index": 1,
+            "date": "2026-10-19",
+            "weekday": "Monday",
+            "condition": "Sunny Intervals",
+            "temperature_max_c": 13,
+            "temperature_max_f": 55,
+            "temperature_min_c": 8,
+            "temperature_min_f": 46,
+            "wind_direction": "Westerly",
+            "wind_speed_mph": 40,
+            "visibility": "Good",
+            "pressure_mb": 1015,
+            "humidity_percent": 70,
+            "uv_risk": 8,
+            "pollution": "High",
+            "sunrise": "07:33 BST",
+            "sunset": "18:08 BST",
+        })
+    );
+}
+
+#[test]
+fn yaml_holds_the_same_document_as_json() {
+    let json: Value =
+        serde_json::from_str(&written(|out| output::write_json(&reports(), out))).unwrap();
+    let yaml: Value =
+        serde_yaml::from_str(&written(|out| output::write_yaml(&reports(), out))).unwrap();
+    assert_eq!(yaml, json);
+}
+
+#[test]
+fn ndjson_has_a_flat_row_per_day() {
+    let ndjson = written(|out| output::write_ndjson(&reports(), out));
+    let rows: Vec<Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    for (index, row) in rows.iter().enumerate() {
+        let columns: Vec<&str> = row
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = ROW_COLUMNS.to_vec();
+        expected.sort_unstable();
+        assert_eq!(columns, expected);
+        assert_eq!(row["schema_version"], SCHEMA_VERSION);
+        assert_eq!(row["location_id"], LONDON);
+        assert_eq!(row["index"], index);
+    }
+    assert_eq!(rows[2]["condition"], "Thundery Showers");
+}
+
+#[test]
+fn csv_has_a_header_and_a_row_per_day() {
+    let (header, rows) = csv_records(&written(|out| output::write_csv(&reports(), out)));
+    assert_eq!(header, ROW_COLUMNS);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0][2], "London, GB");
+    assert_eq!(rows[0][11], "Light Rain");
+    assert_eq!(rows[0][12], "15");
+}
+
+#[test]
+fn csv_has_a_header_without_any_rows() {
+    let csv = written(|out| output::write_csv(&[], out));
+    assert_eq!(csv.lines().count(), 1, "{}", csv);
+    let (header, rows) = csv_records(&csv);
+    assert_eq!(header, ROW_COLUMNS);
+    assert!(rows.is_empty());
+
+    let csv = written(|out| output::write_observations(OutputFormat::Csv, &[], out));
+    assert_eq!(
+        csv_records(&csv),
+        (OBSERVATION_COLUMNS.map(String::from).to_vec(), Vec::new())
+    );
+}
+
+#[test]
+fn observations_follow_the_schema() {
+    let reports = observation_reports();
+    let json = written(|out| output::write_observations(OutputFormat::Json, &reports, out));
+    let document: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(document["schema_version"], SCHEMA_VERSION);
+    let observation = &document["observations"][0];
+    assert_eq!(observation["location"]["name"], "London, GB");
+    assert_eq!(observation["issued"], "2026-10-18T17:00:00+00:00");
+    assert_eq!(observation["observed"], "Sunday - 18:00 BST");
+    assert_eq!(observation["condition"], "Sunny");
+    assert_eq!(observation["temperature_c"], 15);
+    assert_eq!(observation["temperature_f"], 59);
+    assert_eq!(observation["wind_speed_mph"], 12);
+    assert_eq!(observation["humidity_percent"], 71);
+    assert_eq!(observation["pressure_mb"], 1012);
+    assert_eq!(observation["pressure"], "1012mb, Falling");
+
+    let yaml = written(|out| output::write_observations(OutputFormat::Yaml, &reports, out));
+    assert_eq!(serde_yaml::from_str::<Value>(&yaml).unwrap(), document);
+
+    let ndjson = written(|out| output::write_observations(OutputFormat::Ndjson, &reports, out));
+    let row: Value = serde_json::from_str(ndjson.trim_end()).unwrap();
+    assert_eq!(row["location_id"], LONDON);
+    assert_eq!(row["temperature_c"], 15);
+
+    let csv = written(|out| output::write_observations(OutputFormat::Csv, &reports, out));
+    let (header, rows) = csv_records(&csv);
+    assert_eq!(header, OBSERVATION_COLUMNS);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0][16], "1012mb, Falling");
+}
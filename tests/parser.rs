@@ -0,0 +1,78 @@
+use text_weather::error::Error;
+use text_weather::parser::parse_document;
+
+const FORECAST: &str = include_str!("fixtures/forecast.xml");
+
+#[test]
+fn parses_the_fixture() {
+    let channel = parse_document(FORECAST).unwrap();
+    assert_eq!(channel.get_language(), "en");
+    assert_eq!(channel.get_items().len(), 3);
+}
+
+#[test]
+fn reports_where_a_mismatched_tag_is() {
+    let body = "<rss>\n<channel>\n  <title>London</titel>\n</channel>\n</rss>\n";
+    let e = match parse_document(body) {
+        Err(Error::Parse(e)) => e,
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    };
+    assert_eq!(e.line(), 3);
+    assert!(e.column() > 1, "column {}", e.column());
+    assert_eq!(e.path(), "/rss/channel/title");
+}
+
+#[test]
+fn counts_columns_in_characters() {
+    let body = "<rss><channel><title>°°°&bogus;</title></channel></rss>";
+    let e = match parse_document(body) {
+        Err(Error::Parse(e)) => e,
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    };
+    assert_eq!(e.line(), 1);
+    // Just after the text, counting each '°' once rather than as two bytes.
+    assert_eq!(e.column(), 32);
+    assert!(e.to_string().contains("line 1, column 32"));
+}
+
+#[test]
+fn names_a_missing_image() {
+    let start = FORECAST.find("<image>").unwrap();
+    let end = FORECAST.find("</image>").unwrap() + "</image>".len();
+    let body = format!("{}{}", &FORECAST[..start], &FORECAST[end..]);
+    let e = match parse_document(&body) {
+        Err(Error::Parse(e)) => e,
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    };
+    assert_eq!(e.missing_element(), Some("image"));
+    assert_eq!(e.path(), "/rss/channel");
+    assert_eq!(e.line(), 14);
+}
+
+#[test]
+fn names_a_missing_guid() {
+    let start = FORECAST.find("<guid").unwrap();
+    let end = FORECAST.find("</guid>").unwrap() + "</guid>".len();
+    let body = format!("{}{}", &FORECAST[..start], &FORECAST[end..]);
+    let e = match parse_document(&body) {
+        Err(Error::Parse(e)) => e,
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    };
+    assert_eq!(e.missing_element(), Some("guid"));
+    assert_eq!(e.path(), "/rss/channel/item");
+    assert_eq!(e.line(), 11);
+    assert!(e
+        .to_string()
+        .starts_with("Missing <guid> in /rss/channel/item ending at line 11"));
+}
+
+#[test]
+fn a_bare_channel_names_what_it_lacks() {
+    let body = "<rss><channel><title>x</title></channel></rss>";
+    let e = match parse_document(body) {
+        Err(Error::Parse(e)) => e,
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    };
+    assert_eq!(e.missing_element(), Some("link"));
+    assert_eq!(e.path(), "/rss/channel");
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{FORECAST, LONDON};
+use text_weather::dayoption::Day;
+use text_weather::fetcher::Feed;
+use text_weather::prose::{Phrases, Prose, Ssml};
+use text_weather::render::{RenderOptions, Renderer};
+use text_weather::units::Units;
+use text_weather::LocationForecast;
+
+fn forecast(day: Option<Day>, stale: bool) -> LocationForecast {
+    let mut feed = Feed::new(String::from(FORECAST));
+    feed.set_fetched(Utc.with_ymd_and_hms(2026, 10, 17, 21, 5, 0).unwrap());
+    feed.set_stale(stale);
+    LocationForecast::new(LONDON, feed, day).unwrap()
+}
+
+fn rendered<R: Renderer>(renderer: R, forecasts: &[LocationForecast], units: Units) -> String {
+    let mut options = RenderOptions::default();
+    options.set_units(units);
+    let mut out = Vec::new();
+    renderer.render(&mut out, forecasts, &options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn describes_each_day_in_a_sentence() {
+    let prose = rendered(Prose::default(), &[forecast(None, false)], Units::Both);
+    assert_eq!(
+        prose,
+        "Today will be light rain, highs of 15 degrees, lows of 9 degrees, a gentle \
+         south-westerly breeze and low UV. Tomorrow will be sunny intervals, highs of 13 \
+         degrees, lows of 8 degrees, a westerly gale and very high UV. The day after tomorrow \
+         will be thundery showers, highs of 12 degrees, lows of 7 degrees, a light \
+         north-easterly breeze and low UV.\n"
+    );
+}
+
+#[test]
+fn imperial_units_give_fahrenheit() {
+    let prose = rendered(Prose::default(), &[forecast(None, false)], Units::Imperial);
+    assert!(prose.starts_with("Today will be light rain, highs of 59 degrees, lows of 48 degrees"));
+}
+
+#[test]
+fn names_the_chosen_day_as_it_was_asked_for() {
+    for (day, name) in [
+        (Day::Overmorrow, "The day after tomorrow"),
+        ("tuesday".parse().unwrap(), "Tuesday"),
+        ("2026-10-20".parse().unwrap(), "Tuesday"),
+    ] {
+        let prose = rendered(Prose::default(), &[forecast(Some(day), false)], Units::Both);
+        assert!(
+            prose.starts_with(&format!("{} will be thundery showers,", name)),
+            "{}",
+            prose
+        );
+    }
+}
+
+#[test]
+fn warns_of_stale_forecasts_in_the_chosen_language() {
+    let prose = rendered(Prose::default(), &[forecast(None, true)], Units::Both);
+    assert!(
+        prose.starts_with(
+            "BBC Weather could not be reached, so this forecast from 21:05 on 17 October may be \
+             out of date. Today"
+        ),
+        "{}",
+        prose
+    );
+
+    let mut phrases = Phrases {
+        stale: String::from("Rhagolwg o {fetched}."),
+        fetched_format: String::from("{time} ar {date} {month}"),
+        sentence: String::from("{day}: {condition}, {details}."),
+        ..Phrases::default()
+    };
+    phrases.months[9] = String::from("Hydref");
+    phrases.days[0] = String::from("heddiw");
+    let prose = rendered(Prose::new(phrases), &[forecast(None, true)], Units::Both);
+    assert!(
+        prose.starts_with("Rhagolwg o 21:05 ar 17 Hydref. Heddiw: light rain,"),
+        "{}",
+        prose
+    );
+}
+
+#[test]
+fn introduces_each_location_when_there_are_several() {
+    let prose = rendered(
+        Prose::default(),
+        &[forecast(None, false), forecast(None, false)],
+        Units::Both,
+    );
+    let paragraphs: Vec<&str> = prose.split("\n\n").collect();
+    assert_eq!(paragraphs.len(), 2);
+    assert!(paragraphs
+        .iter()
+        .all(|paragraph| paragraph.starts_with("The forecast for London, GB. Today")));
+}
+
+#[test]
+fn ssml_escapes_each_sentence() {
+    let sentence = String::from("{day} <{condition}> & more.");
+    let phrases = Phrases {
+        sentence: sentence.clone(),
+        brief_sentence: sentence,
+        ..Phrases::default()
+    };
+    let ssml = rendered(Ssml::new(phrases), &[forecast(None, false)], Units::Both);
+    assert!(ssml.contains(r#"xml:lang="en""#), "{}", ssml);
+    assert!(
+        ssml.contains("<s>Today &lt;light rain&gt; &amp; more.</s>"),
+        "{}",
+        ssml
+    );
+    assert_eq!(ssml.matches("<s>").count(), 3);
+}
+
+#[test]
+fn placeholders_in_the_feed_are_left_alone() {
+    let body = FORECAST
+        .replace("Light Rain", "Light {details} Rain")
+        .replace("London, GB", "{location} {day}");
+    let forecast = || LocationForecast::new(LONDON, Feed::new(body.clone()), None).unwrap();
+    let prose = rendered(Prose::default(), &[forecast(), forecast()], Units::Both);
+    assert!(
+        prose.starts_with(
+            "The forecast for {location} {day}. Today will be light {details} rain, highs of 15"
+        ),
+        "{}",
+        prose
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::io::{self, Write};
+
+use common::{FORECAST, LONDON};
+use text_weather::error::Error;
+use text_weather::fetcher::Feed;
+use text_weather::render::{RenderOptions, Renderer, Renderers};
+use text_weather::LocationForecast;
+
+/// A line for each day's summary, as in the module documentation.
+struct Summaries;
+
+impl Renderer for Summaries {
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        forecasts: &[LocationForecast],
+        _: &RenderOptions,
+    ) -> io::Result<()> {
+        for forecast in forecasts {
+            for (_, daily) in forecast.days() {
+                writeln!(out, "{}", daily.summary().summary())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn rendered(renderers: &Renderers, name: &str) -> Result<String, Error> {
+    let forecast = LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), None).unwrap();
+    let mut out = Vec::new();
+    renderers.render(name, &mut out, &[forecast], &RenderOptions::default())?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn custom_renderers_are_chosen_by_name() {
+    let mut renderers = Renderers::default();
+    let built_in = renderers.names().count();
+    renderers.register("summaries", Summaries);
+    assert_eq!(renderers.names().count(), built_in + 1);
+    assert!(renderers.names().any(|name| name == "summaries"));
+    assert!(renderers.get("summaries").is_some());
+    assert_eq!(
+        rendered(&renderers, "summaries").unwrap(),
+        "Light Rain\nSunny Intervals\nThundery Showers\n"
+    );
+    // The built in renderers are still there beside it.
+    assert!(rendered(&renderers, "json")
+        .unwrap()
+        .contains("\"schema_version\""));
+}
+
+#[test]
+fn registering_a_built_in_name_replaces_it() {
+    let mut renderers = Renderers::default();
+    let built_in = renderers.names().count();
+    renderers.register("text", Summaries);
+    assert_eq!(renderers.names().count(), built_in);
+    assert_eq!(
+        rendered(&renderers, "text").unwrap(),
+        "Light Rain\nSunny Intervals\nThundery Showers\n"
+    );
+}
+
+#[test]
+fn unknown_renderers_list_the_known_ones() {
+    let mut renderers = Renderers::default();
+    renderers.register("summaries", Summaries);
+    match rendered(&renderers, "sumaries") {
+        Err(Error::UnknownRenderer { name, available }) => {
+            assert_eq!(name, "sumaries");
+            assert!(available.iter().any(|name| name == "summaries"));
+            assert!(available.iter().any(|name| name == "text"));
+        }
+        other => panic!("expected UnknownRenderer, got {:?}", other),
+    }
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use common::{Reply, Server, FORECAST, LONDON};
+use text_weather::error::Error;
+use text_weather::fetcher::{Fetcher, HttpFetcher, HttpOptions};
+
+fn fetch(options: HttpOptions) -> Result<String, Error> {
+    let fetcher = HttpFetcher::new(options).unwrap();
+    fetcher.fetch(LONDON).map(|feed| String::from(feed.body()))
+}
+
+#[test]
+fn retries_server_errors_until_one_succeeds() {
+    let server = Server::start(vec![
+        Reply::status(503),
+        Reply::status(502),
+        Reply::feed(FORECAST),
+    ]);
+    let mut options = server.options();
+    options.set_retries(3);
+    assert_eq!(fetch(options).unwrap(), FORECAST);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn gives_up_after_the_last_retry() {
+    let server = Server::start(vec![Reply::status(500)]);
+    let mut options = server.options();
+    options.set_retries(2);
+    match fetch(options) {
+        Err(Error::ServerError { status, .. }) => assert_eq!(status, 500),
+        other => panic!("expected ServerError, got {:?}", other),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn does_not_retry_permanent_failures() {
+    for status in [404, 403] {
+        let server = Server::start(vec![Reply::status(status)]);
+        let mut options = server.options();
+        options.set_retries(3);
+        assert!(fetch(options).is_err());
+        assert_eq!(server.requests().len(), 1, "status {}", status);
+    }
+}
+
+#[test]
+fn retries_after_being_rate_limited() {
+    let server = Server::start(vec![
+        Reply::status(429).header("Retry-After", "0"),
+        Reply::feed(FORECAST),
+    ]);
+    assert_eq!(fetch(server.options()).unwrap(), FORECAST);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn waits_as_long_as_retry_after_asks() {
+    let server = Server::start(vec![
+        Reply::status(429).header("Retry-After", "1"),
+        Reply::feed(FORECAST),
+    ]);
+    let mut options = server.options();
+    options.set_max_backoff(Duration::from_secs(2));
+    let started = Instant::now();
+    assert_eq!(fetch(options).unwrap(), FORECAST);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[test]
+fn gives_up_when_retry_after_exceeds_the_maximum_backoff() {
+    let server = Server::start(vec![
+        Reply::status(429).header("Retry-After", "120"),
+        Reply::feed(FORECAST),
+    ]);
+    let started = Instant::now();
+    match fetch(server.options()) {
+        Err(Error::RateLimited { retry_after, .. }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(120)))
+        }
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn reads_retry_after_as_seconds_or_a_date() {
+    let in_a_minute = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+    let cases = [
+        ("30", Some(30..=30)),
+        (in_a_minute.as_str(), Some(55..=60)),
+        ("soon", None),
+    ];
+    for (value, expected) in cases.iter() {
+        let server = Server::start(vec![Reply::status(429).header("Retry-After", value)]);
+        let mut options = server.options();
+        options.set_retries(0);
+        match fetch(options) {
+            Err(Error::RateLimited { retry_after, .. }) => match (retry_after, expected) {
+                (Some(delay), Some(range)) => {
+                    assert!(range.contains(&delay.as_secs()), "{}: {:?}", value, delay)
+                }
+                (None, None) => (),
+                _ => panic!("{}: expected {:?}, got {:?}", value, expected, retry_after),
+            },
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn backs_off_exponentially() {
+    let server = Server::start(vec![Reply::status(503)]);
+    let mut options = server.options();
+    options.set_retries(2);
+    options.set_initial_backoff(Duration::from_millis(100));
+    options.set_max_backoff(Duration::from_secs(1));
+    let started = Instant::now();
+    assert!(fetch(options).is_err());
+    // At least half of each 100ms and 200ms delay, whatever the jitter.
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[test]
+fn caps_backoff_at_the_maximum() {
+    let server = Server::start(vec![Reply::status(503)]);
+    let mut options = server.options();
+    options.set_retries(2);
+    options.set_initial_backoff(Duration::from_secs(60));
+    options.set_max_backoff(Duration::from_millis(20));
+    let started = Instant::now();
+    assert!(fetch(options).is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn times_out_a_slow_server() {
+    let server = Server::start(vec![Reply::feed(FORECAST).delay(Duration::from_secs(3))]);
+    let mut options = server.options();
+    options.set_retries(1);
+    options.set_timeout(Duration::from_millis(200));
+    let started = Instant::now();
+    match fetch(options) {
+        Err(Error::Reqwest { source, .. }) => assert!(source.is_timeout(), "{:?}", source),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(3));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn retries_after_a_timeout() {
+    let server = Server::start(vec![
+        Reply::feed(FORECAST).delay(Duration::from_secs(3)),
+        Reply::feed(FORECAST),
+    ]);
+    let mut options = server.options();
+    options.set_retries(1);
+    options.set_timeout(Duration::from_millis(200));
+    assert_eq!(fetch(options).unwrap(), FORECAST);
+}
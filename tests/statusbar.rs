@@ -0,0 +1,70 @@
+mod common;
+
+use common::{FORECAST, LONDON};
+use serde_json::Value;
+use text_weather::fetcher::Feed;
+use text_weather::render::{RenderOptions, Renderers};
+use text_weather::LocationForecast;
+
+fn rendered(name: &str, forecast: &str) -> String {
+    let forecast = LocationForecast::new(LONDON, Feed::new(String::from(forecast)), None).unwrap();
+    let mut out = Vec::new();
+    Renderers::default()
+        .render(name, &mut out, &[forecast], &RenderOptions::default())
+        .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn stale() -> Feed {
+    let mut feed = Feed::new(String::from(FORECAST));
+    feed.set_stale(true);
+    feed
+}
+
+#[test]
+fn i3bar_prints_a_single_block() {
+    let i3bar = rendered("i3bar", FORECAST);
+    assert_eq!(i3bar.lines().count(), 1, "{}", i3bar);
+    let block: Value = serde_json::from_str(&i3bar).unwrap();
+    assert_eq!(block["name"], "text_weather");
+    assert_eq!(block["instance"], LONDON);
+    assert_eq!(block["full_text"], "Light Rain 15°C (59°F)");
+    assert_eq!(block["short_text"], "15°C (59°F)");
+    assert_eq!(block["markup"], "none");
+    assert!(block.get("color").is_none());
+}
+
+#[test]
+fn i3bar_joins_locations_into_the_block() {
+    let forecasts = [
+        LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), None).unwrap(),
+        LocationForecast::new("2654675", stale(), None).unwrap(),
+    ];
+    let mut out = Vec::new();
+    Renderers::default()
+        .render("i3bar", &mut out, &forecasts, &RenderOptions::default())
+        .unwrap();
+    let block: Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(block["instance"], "2643743,2654675");
+    assert_eq!(
+        block["full_text"],
+        "Light Rain 15°C (59°F) | Light Rain 15°C (59°F) (stale)"
+    );
+    assert_eq!(block["color"], "#888888");
+}
+
+#[test]
+fn waybar_escapes_pango_markup() {
+    let forecast = FORECAST
+        .replace("London, GB", "Brighton &amp; Hove, GB")
+        .replace("Light Rain", "Light Rain &lt;heavy&gt;");
+    let status: Value = serde_json::from_str(&rendered("waybar", &forecast)).unwrap();
+    assert_eq!(status["text"], "Light Rain &lt;heavy&gt; 15°C (59°F)");
+    let tooltip = status["tooltip"].as_str().unwrap();
+    assert!(
+        tooltip.starts_with("Brighton &amp; Hove, GB\n"),
+        "{}",
+        tooltip
+    );
+    assert_eq!(status["class"], serde_json::json!(["rain"]));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{FORECAST, LONDON};
+use text_weather::dayoption::Day;
+use text_weather::fetcher::Feed;
+use text_weather::icon::Icons;
+use text_weather::render::{RenderOptions, Renderers};
+use text_weather::table::{self, Borders};
+use text_weather::LocationForecast;
+
+/// The forecast as a table in ASCII, no wider than `width`.
+fn table(width: usize, day: Option<Day>) -> String {
+    let forecast = LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), day).unwrap();
+    let mut options = RenderOptions::default();
+    options.set_width(Some(width));
+    options.set_borders(Borders::Ascii);
+    options.set_icons(Icons::Ascii);
+    let mut out = Vec::new();
+    Renderers::default()
+        .render("table", &mut out, &[forecast], &options)
+        .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn wraps_between_words() {
+    assert_eq!(table::wrap("Sunny Intervals", 15), ["Sunny Intervals"]);
+    assert_eq!(table::wrap("Sunny Intervals", 10), ["Sunny", "Intervals"]);
+    assert_eq!(
+        table::wrap("Light Rain Showers", 10),
+        ["Light Rain", "Showers"]
+    );
+    assert_eq!(table::wrap("  extra   spaces ", 20), ["extra spaces"]);
+}
+
+#[test]
+fn splits_words_longer_than_a_line() {
+    assert_eq!(table::wrap("Intervals", 4), ["Inte", "rval", "s"]);
+    assert_eq!(
+        table::wrap("Sunny Intervals", 6),
+        ["Sunny", "Interv", "als"]
+    );
+    // Widths are of what is shown, so ° is one column and never split.
+    assert_eq!(table::wrap("15°C (59°F)", 5), ["15°C", "(59°F", ")"]);
+    // A line always holds at least one character.
+    assert_eq!(table::wrap("ab", 0), ["a", "b"]);
+}
+
+#[test]
+fn empty_text_is_one_empty_line() {
+    assert_eq!(table::wrap("", 10), [""]);
+    assert_eq!(table::wrap("   ", 10), [""]);
+}
+
+#[test]
+fn tables_fit_their_width() {
+    for width in [200, 80, 60, 44, 40, 32] {
+        let table = table(width, None);
+        for line in table.lines() {
+            assert!(line.chars().count() <= width, "{}:\n{}", width, table);
+        }
+    }
+}
+
+#[test]
+fn narrow_tables_abbreviate_days_before_splitting_words() {
+    let wide = table(200, None);
+    assert!(wide.contains("| Tomorrow "), "{}", wide);
+
+    let narrow = table(44, None);
+    assert!(narrow.contains("| Sun "), "{}", narrow);
+    assert!(narrow.contains("| Mon "), "{}", narrow);
+    for word in ["Intervals", "Thundery", "Moderate"] {
+        assert!(narrow.contains(word), "{} was split:\n{}", word, narrow);
+    }
+
+    // Too narrow for every word, so only the fewest are split.
+    let narrower = table(40, None);
+    for word in ["Thundery", "Moderate", "Summary"] {
+        assert!(narrower.contains(word), "{} was split:\n{}", word, narrower);
+    }
+}
+
+#[test]
+fn short_day_names_follow_the_chosen_day() {
+    let table = table(20, Some(Day::Tomorrow));
+    assert!(table.contains("| Mon "), "{}", table);
+}
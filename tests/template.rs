@@ -0,0 +1,91 @@
+mod common;
+
+use common::{FORECAST, LONDON};
+use text_weather::error::Error;
+use text_weather::fetcher::Feed;
+use text_weather::render::{RenderOptions, Renderer};
+use text_weather::template::Template;
+use text_weather::units::Units;
+use text_weather::LocationForecast;
+
+/// The column and reason `template` fails to parse with.
+fn parse_error(template: &str) -> (usize, String) {
+    match Template::parse(template) {
+        Err(Error::Template {
+            template: failed,
+            column,
+            reason,
+        }) => {
+            assert_eq!(failed, template);
+            (column, reason)
+        }
+        other => panic!(
+            "{:?} should not parse, got {:?}",
+            template,
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+fn unmatched_braces_are_reported_where_they_are() {
+    let (column, reason) = parse_error("{day}: {condition");
+    assert_eq!(column, 8);
+    assert!(reason.contains("never closed"), "{}", reason);
+
+    let (column, reason) = parse_error("{day}} {condition}");
+    assert_eq!(column, 6);
+    assert!(reason.contains("without a matching '{'"), "{}", reason);
+}
+
+#[test]
+fn columns_count_characters_rather_than_bytes() {
+    let (column, _) = parse_error("{min}–{max} °C {nonsense}");
+    assert_eq!(column, 16);
+}
+
+#[test]
+fn unknown_placeholders_list_the_known_ones() {
+    let (column, reason) = parse_error("{day}: {nonsense}");
+    assert_eq!(column, 8);
+    assert!(
+        reason.starts_with("unknown placeholder {nonsense}"),
+        "{}",
+        reason
+    );
+    assert!(reason.contains("condition"), "{}", reason);
+}
+
+#[test]
+fn units_and_precisions_are_checked() {
+    let (_, reason) = parse_error("{day:c}");
+    assert_eq!(reason, "{day} does not take a unit or precision");
+
+    let (_, reason) = parse_error("{wind_speed:furlongs}");
+    assert_eq!(
+        reason,
+        "{wind_speed} cannot be shown in \"furlongs\", expected mph, kmh, ms, kn"
+    );
+
+    let (_, reason) = parse_error("{max:c.7}");
+    assert!(reason.starts_with("\"7\" is not a precision"), "{}", reason);
+    let (_, reason) = parse_error("{max:.x}");
+    assert!(reason.starts_with("\"x\" is not a precision"), "{}", reason);
+}
+
+#[test]
+fn fills_in_a_line_a_day() {
+    let template =
+        Template::parse("{{{weekday}}} {condition}: {min:c}-{max:F}, {wind_speed:kmh.1}").unwrap();
+    let forecast = LocationForecast::new(LONDON, Feed::new(String::from(FORECAST)), None).unwrap();
+    let mut options = RenderOptions::default();
+    options.set_units(Units::Metric);
+    let mut out = Vec::new();
+    Renderer::render(&template, &mut out, &[forecast], &options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{Sunday} Light Rain: 9°C-59°F, 19.3km/h\n\
+         {Monday} Sunny Intervals: 8°C-55°F, 64.4km/h\n\
+         {Tuesday} Thundery Showers: 7°C-54°F, 8.0km/h\n"
+    );
+}